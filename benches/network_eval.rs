@@ -0,0 +1,66 @@
+// Compares evaluation speed of Network, Network::activate_batch and CompiledNetwork.
+// Run with `cargo bench`.
+use std::time::{Duration, Instant};
+
+use aster_ml::*;
+use rand::prelude::*;
+use rand::rngs::StdRng;
+
+fn random_genome(rng: &mut StdRng) -> Genome {
+    let mut genome = Genome::new(8, 3);
+    for _ in 0..60 {
+        genome.rand_connected_child();
+    }
+    for _ in 0..20 {
+        genome.random_synapse();
+    }
+    for gene in genome.genes.iter_mut() {
+        if gene.flag[0] == 10 {
+            gene.extern_data = rng.gen_range(-1.0..1.0);
+        } else {
+            gene.local_data = rng.gen_range(-0.5..0.5);
+        }
+    }
+    genome
+}
+
+fn time<F: FnMut() -> f32>(name: &str, mut run: F) -> Duration {
+    let start = Instant::now();
+    let checksum = run();
+    let elapsed = start.elapsed();
+    println!("{:<32} {:>12.3?}   (checksum {:.4})", name, elapsed, checksum);
+    elapsed
+}
+
+fn main() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let genome = random_genome(&mut rng);
+    let rows: Vec<Vec<f32>> = (0..5000).map(|_| (0..8).map(|_| rng.gen_range(-1.0..1.0)).collect()).collect();
+    let mut network = Network::from_genome(genome.clone());
+    println!("{} neurons, {} synapses, {} rows", network.neurons.len(), network.synapses.len(), rows.len());
+
+    let baseline = time("Network::activate", || {
+        rows.iter()
+            .map(|row| {
+                network.reset();
+                network.activate(row).iter().sum::<f32>()
+            })
+            .sum()
+    });
+    time("Network::activate_batch", || network.activate_batch(&rows).iter().flatten().sum());
+    let compile = time("CompiledNetwork::from_genome", || CompiledNetwork::from_genome(&genome).biases.iter().sum());
+    let mut compiled = CompiledNetwork::from_genome(&genome);
+    let fast = time("CompiledNetwork::activate", || {
+        rows.iter()
+            .map(|row| {
+                compiled.reset();
+                compiled.activate(row).iter().sum::<f32>()
+            })
+            .sum()
+    });
+    println!(
+        "Compiled speedup: {:.1}x (compile cost {:.3?})",
+        baseline.as_secs_f64() / fast.as_secs_f64(),
+        compile
+    );
+}
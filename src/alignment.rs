@@ -0,0 +1,236 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::visuals::apply_layout_options;
+use crate::{convert_f32_to_id, get_neuron_height, GeneType, Genome, SynapseStatus, VisualizeOptions};
+use plotly::{
+    common::{Anchor, DashType, HoverInfo, Line, Marker, MarkerSymbol, Mode},
+    layout::{Annotation, Axis},
+    Layout, Plot, Scatter,
+};
+
+/// Opacity of parent genes that the child did not inherit
+const NOT_INHERITED_OPACITY: f64 = 0.3;
+
+/// Where a gene sits relative to the two parents. Genes are aligned by neuron id,
+/// and synapses by source and destination, the same positional ids crossover lines genes up by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum GeneAlignment {
+    /// Present in both parents
+    Matching,
+    /// Present only in the first parent
+    DisjointA,
+    /// Present only in the second parent
+    DisjointB,
+    /// Present in the child but in neither parent
+    Novel,
+}
+
+impl GeneAlignment {
+    fn label(&self) -> &'static str {
+        match self {
+            GeneAlignment::Matching => "Matching",
+            GeneAlignment::DisjointA => "Disjoint, parent A",
+            GeneAlignment::DisjointB => "Disjoint, parent B",
+            GeneAlignment::Novel => "In neither parent",
+        }
+    }
+
+    fn color(&self) -> &'static str {
+        match self {
+            GeneAlignment::Matching => "#2b6cb0",
+            GeneAlignment::DisjointA => "#188038",
+            GeneAlignment::DisjointB => "#9334e6",
+            GeneAlignment::Novel => "#e37400",
+        }
+    }
+}
+
+/// Positional key of a gene: the neuron id, or source and destination of a synapse
+type GeneKey = ([u8; 2], Option<[u8; 2]>);
+/// Trace style: alignment, enabled (always true for neurons) and inherited by the child
+type GeneStyle = (GeneAlignment, bool, bool);
+/// Trace points: x, y and hover text
+type Points = (Vec<f64>, Vec<f64>, Vec<String>);
+
+fn gene_keys(genome: &Genome) -> HashMap<GeneKey, usize> {
+    genome
+        .genes
+        .iter()
+        .enumerate()
+        .filter_map(|(index, gene)| match GeneType::from_u8(gene.flag[0]) {
+            Some(GeneType::Synapse) => Some(((gene.id, Some(convert_f32_to_id(gene.local_data))), index)),
+            Some(_) => Some(((gene.id, None), index)),
+            None => None,
+        })
+        .collect()
+}
+
+// Crossover debugging
+impl Genome {
+    /// Three aligned tree plots of two parents and their child.
+    /// Genes are colored by `GeneAlignment`: in the parents matching or disjoint, in the child by which parent
+    /// the gene could have come from, with genes in neither parent marked. Parent genes missing from the child
+    /// are faded. All panels share the `tree_layout` axes, so a tree position sits at the same spot in each.
+    pub fn crossover_plot(parent_a: &Genome, parent_b: &Genome, child: &Genome, options: &VisualizeOptions) -> Plot {
+        let (keys_a, keys_b, keys_child) = (gene_keys(parent_a), gene_keys(parent_b), gene_keys(child));
+        let alignment = |key: &GeneKey| match (keys_a.contains_key(key), keys_b.contains_key(key)) {
+            (true, true) => GeneAlignment::Matching,
+            (true, false) => GeneAlignment::DisjointA,
+            (false, true) => GeneAlignment::DisjointB,
+            (false, false) => GeneAlignment::Novel,
+        };
+
+        let mut positions = parent_a.tree_layout();
+        positions.extend(parent_b.tree_layout());
+        positions.extend(child.tree_layout());
+
+        let mut plot = Plot::new();
+        let mut shown_groups = Vec::new();
+        let panels = [("Parent A", parent_a, &keys_a), ("Parent B", parent_b, &keys_b), ("Child", child, &keys_child)];
+        for (panel, (_, genome, keys)) in panels.iter().enumerate() {
+            let x_axis = if panel == 0 { "x".to_string() } else { format!("x{}", panel + 1) };
+            let is_child = panel == 2;
+
+            // Bucket genes into one trace per alignment, gene kind and style
+            let mut sorted: Vec<(&GeneKey, &usize)> = keys.iter().collect();
+            sorted.sort();
+            let mut edges: BTreeMap<GeneStyle, Points> = BTreeMap::new();
+            let mut nodes: BTreeMap<GeneStyle, Points> = BTreeMap::new();
+            let mut midpoints: Points = (Vec::new(), Vec::new(), Vec::new());
+            for (key, &index) in sorted {
+                let gene = &genome.genes[index];
+                let class = alignment(key);
+                let inherited = is_child || keys_child.contains_key(key);
+                match key.1 {
+                    Some(to) => {
+                        let (Some(&(x0, y0)), Some(&(x1, y1))) = (positions.get(&key.0), positions.get(&to)) else {
+                            continue;
+                        };
+                        let enabled = gene.flag[1] == SynapseStatus::Enabled as u8;
+                        let edge = edges.entry((class, enabled, inherited)).or_default();
+                        edge.0.extend([x0, x1, f64::NAN]);
+                        edge.1.extend([y0, y1, f64::NAN]);
+                        midpoints.0.push((x0 + x1) / 2.0);
+                        midpoints.1.push((y0 + y1) / 2.0);
+                        midpoints.2.push(format!(
+                            "Synapse {:?} -> {:?}<br>Weight: {}<br>{}<br>{}",
+                            key.0,
+                            to,
+                            gene.extern_data,
+                            if enabled { "enabled" } else { "disabled" },
+                            class.label()
+                        ));
+                    }
+                    None => {
+                        let Some(&(x, y)) = positions.get(&key.0) else { continue };
+                        let node = nodes.entry((class, true, inherited)).or_default();
+                        node.0.push(x);
+                        node.1.push(y);
+                        node.2.push(format!(
+                            "ID: {:?}, Type: {:?}<br>Bias: {}<br>Children: {}<br>Tree {}, height {}<br>{}",
+                            gene.id,
+                            GeneType::from_u8(gene.flag[0]).unwrap(),
+                            gene.local_data,
+                            gene.flag[1],
+                            gene.id[0],
+                            get_neuron_height(gene.id[1]),
+                            class.label()
+                        ));
+                    }
+                }
+            }
+
+            let mut legend = |class: GeneAlignment| {
+                let first = !shown_groups.contains(&class);
+                shown_groups.push(class);
+                first
+            };
+            for ((class, enabled, inherited), (x, y, _)) in edges {
+                let mut line = Line::new().color(class.color()).width(1.5);
+                if !enabled {
+                    line = line.dash(DashType::Dash);
+                }
+                plot.add_trace(
+                    Scatter::new(x, y)
+                        .mode(Mode::Lines)
+                        .name(class.label())
+                        .legend_group(class.label())
+                        .show_legend(legend(class))
+                        .opacity(if inherited { 1.0 } else { NOT_INHERITED_OPACITY })
+                        .line(line)
+                        .hover_info(HoverInfo::None)
+                        .x_axis(&x_axis),
+                );
+            }
+            plot.add_trace(
+                Scatter::new(midpoints.0, midpoints.1)
+                    .mode(Mode::Markers)
+                    .name("Synapses")
+                    .show_legend(false)
+                    .marker(Marker::new().size(8).color("rgba(0,0,0,0)"))
+                    .hover_text_array(midpoints.2)
+                    .hover_info(HoverInfo::Text)
+                    .x_axis(&x_axis),
+            );
+            for ((class, _, inherited), (x, y, text)) in nodes {
+                plot.add_trace(
+                    Scatter::new(x, y)
+                        .mode(Mode::Markers)
+                        .name(class.label())
+                        .legend_group(class.label())
+                        .show_legend(legend(class))
+                        .opacity(if inherited { 1.0 } else { NOT_INHERITED_OPACITY })
+                        .marker(Marker::new().size(10).symbol(MarkerSymbol::Star).color(class.color()))
+                        .hover_text_array(text)
+                        .hover_info(HoverInfo::Text)
+                        .x_axis(&x_axis),
+                );
+            }
+        }
+
+        // Side by side panels sharing the y axis and one x range
+        let (min_x, max_x) = positions.values().fold((f64::MAX, f64::MIN), |(low, high), &(x, _)| (low.min(x), high.max(x)));
+        let domains = [[0.0, 0.31], [0.345, 0.655], [0.69, 1.0]];
+        let x_axis = |domain: &[f64]| {
+            let axis = Axis::new().domain(domain).range(vec![min_x - 0.5, max_x + 0.5]).anchor("y");
+            if options.hide_axes {
+                axis.visible(false).show_grid(false).zero_line(false)
+            } else {
+                axis
+            }
+        };
+        let mut y_axis = Axis::new().range(vec![-0.5, 8.5]);
+        if options.hide_axes {
+            y_axis = y_axis.visible(false).show_grid(false).zero_line(false);
+        }
+        let titles = panels
+            .iter()
+            .zip(domains)
+            .map(|((title, _, _), domain)| {
+                Annotation::new()
+                    .text(*title)
+                    .x((domain[0] + domain[1]) / 2.0)
+                    .y(1.0)
+                    .x_ref("paper")
+                    .y_ref("paper")
+                    .x_anchor(Anchor::Center)
+                    .y_anchor(Anchor::Bottom)
+                    .show_arrow(false)
+            })
+            .collect();
+
+        let options = VisualizeOptions {
+            title: Some(options.title.clone().unwrap_or_else(|| "Crossover alignment".to_string())),
+            hide_axes: false,
+            ..options.clone()
+        };
+        let layout = apply_layout_options(Layout::new(), &options)
+            .x_axis(x_axis(&domains[0]))
+            .x_axis2(x_axis(&domains[1]))
+            .x_axis3(x_axis(&domains[2]))
+            .y_axis(y_axis)
+            .annotations(titles);
+        plot.set_layout(layout);
+        plot
+    }
+}
@@ -0,0 +1,48 @@
+// Converts between genome codes and genome files.
+//
+// Usage: aster_genome decode <code | -> [--out <file>]
+//        aster_genome encode <file>
+// decode prints the genome and optionally saves it, reading the code from stdin when given "-".
+// encode prints the code of a genome file written by `Genome::save`.
+use std::io::{self, Read};
+use std::process::ExitCode;
+
+use aster_ml::*;
+
+fn run(args: &[String]) -> Result<(), String> {
+    match args {
+        [command, code, rest @ ..] if command == "decode" => {
+            let code = if code == "-" {
+                let mut code = String::new();
+                io::stdin().read_to_string(&mut code).map_err(|err| err.to_string())?;
+                code
+            } else {
+                code.clone()
+            };
+            let genome = Genome::from_code(&code).map_err(|err| err.to_string())?;
+            genome.display();
+            match rest {
+                [] => Ok(()),
+                [flag, path] if flag == "--out" => genome.save(path).map_err(|err| err.to_string()),
+                _ => Err(format!("unexpected arguments {:?}", rest)),
+            }
+        }
+        [command, path] if command == "encode" => {
+            let genome = Genome::load(path).map_err(|err| err.to_string())?;
+            println!("{}", genome.to_code());
+            Ok(())
+        }
+        _ => Err("usage: aster_genome decode <code | -> [--out <file>]\n       aster_genome encode <file>".to_string()),
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
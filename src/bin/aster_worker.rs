@@ -0,0 +1,112 @@
+// Reference worker for `aster_ml::remote`. Scores genomes and runs streaming episodes on XOR.
+//
+// Usage: aster_worker [--listen tcp:<address> | --listen unix:<path>] [--delay-ms <ms>] [--exit-after <requests>]
+// Without --listen it serves one connection on stdin/stdout. --delay-ms and --exit-after
+// simulate slow and crashing workers.
+use std::io::{self, BufReader};
+use std::thread;
+use std::time::Duration;
+
+use aster_ml::remote::*;
+use aster_ml::*;
+
+const XOR: [([f32; 2], f32); 4] = [([0.0, 0.0], -1.0), ([0.0, 1.0], 1.0), ([1.0, 0.0], 1.0), ([1.0, 1.0], -1.0)];
+
+#[derive(Default)]
+struct XorWorker {
+    delay: Duration,
+    exit_after: Option<usize>,
+    requests: usize,
+    case: usize,
+    squared_error: f32,
+}
+
+impl XorWorker {
+    fn begin_request(&mut self) {
+        self.requests += 1;
+        if self.exit_after.is_some_and(|limit| self.requests > limit) {
+            std::process::exit(1);
+        }
+        thread::sleep(self.delay);
+    }
+}
+
+impl WorkerHandler for XorWorker {
+    fn evaluate_genome(&mut self, genome: Genome) -> Result<f32, String> {
+        self.begin_request();
+        let mut network = Network::from_genome(genome);
+        if network.input_count() != 2 || network.output_count() != 1 {
+            return Err("XOR needs 2 inputs and 1 output".to_string());
+        }
+        let squared_error: f32 = XOR
+            .iter()
+            .map(|(inputs, target)| {
+                network.reset();
+                (network.activate(inputs)[0] - target).powi(2)
+            })
+            .sum();
+        Ok(4.0 - squared_error)
+    }
+
+    fn reset(&mut self, input_count: usize, output_count: usize) -> Result<Vec<f32>, String> {
+        self.begin_request();
+        if input_count != 2 || output_count != 1 {
+            return Err("XOR needs 2 inputs and 1 output".to_string());
+        }
+        self.case = 0;
+        self.squared_error = 0.0;
+        Ok(XOR[0].0.to_vec())
+    }
+
+    fn step(&mut self, action: &[f32]) -> Result<EnvironmentStep, String> {
+        self.squared_error += (action[0] - XOR[self.case].1).powi(2);
+        self.case += 1;
+        Ok(match XOR.get(self.case) {
+            Some((inputs, _)) => EnvironmentStep::Observation(inputs.to_vec()),
+            None => EnvironmentStep::Done(4.0 - self.squared_error),
+        })
+    }
+}
+
+/// A failed connection only ends that connection, the worker keeps listening for the next one
+fn log_connection_error(served: io::Result<()>) {
+    if let Err(err) = served {
+        eprintln!("aster_worker: connection failed: {}", err);
+    }
+}
+
+fn main() -> io::Result<()> {
+    let mut worker = XorWorker::default();
+    let mut listen = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().unwrap_or_else(|| panic!("{} needs a value", arg));
+        match arg.as_str() {
+            "--listen" => listen = Some(value()),
+            "--delay-ms" => worker.delay = Duration::from_millis(value().parse().expect("--delay-ms needs a number")),
+            "--exit-after" => worker.exit_after = Some(value().parse().expect("--exit-after needs a number")),
+            other => panic!("unknown argument {}", other),
+        }
+    }
+
+    match listen.as_deref().map(|target| target.split_once(':').expect("--listen needs tcp:<address> or unix:<path>")) {
+        None => serve_worker(io::stdin().lock(), io::stdout().lock(), &mut worker),
+        Some(("tcp", address)) => {
+            for stream in std::net::TcpListener::bind(address)?.incoming() {
+                let served = stream.and_then(|stream| serve_worker(BufReader::new(stream.try_clone()?), stream, &mut worker));
+                log_connection_error(served);
+            }
+            Ok(())
+        }
+        #[cfg(unix)]
+        Some(("unix", path)) => {
+            let _ = std::fs::remove_file(path);
+            for stream in std::os::unix::net::UnixListener::bind(path)?.incoming() {
+                let served = stream.and_then(|stream| serve_worker(BufReader::new(stream.try_clone()?), stream, &mut worker));
+                log_connection_error(served);
+            }
+            Ok(())
+        }
+        Some((scheme, _)) => panic!("unsupported transport {}", scheme),
+    }
+}
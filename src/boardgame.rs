@@ -0,0 +1,234 @@
+use crate::{Genome, Network};
+
+/// A two-player, turn-based game on a fixed board.
+/// Players are numbered 0 (moves first) and 1.
+pub trait BoardGame: Clone {
+    /// Number of network inputs, one per board cell
+    const CELLS: u16;
+    /// Number of network outputs, one per possible move
+    const MOVES: u16;
+
+    fn new() -> Self;
+    /// Player whose turn it is
+    fn to_move(&self) -> usize;
+    fn legal_moves(&self) -> Vec<usize>;
+    fn play(&mut self, mv: usize);
+    /// Winning player, if any
+    fn winner(&self) -> Option<usize>;
+    fn is_over(&self) -> bool {
+        self.winner().is_some() || self.legal_moves().is_empty()
+    }
+    /// Board from the view of `player`: 1.0 own stone, -1.0 opponent stone, 0.0 empty
+    fn encode(&self, player: usize) -> Vec<f32>;
+
+    /// Creates an empty network with the inputs and outputs a player of this game expects
+    fn new_network() -> Network {
+        Network::from_genome(Genome::new(Self::CELLS, Self::MOVES))
+    }
+}
+
+fn encode_cells(cells: &[Option<usize>], player: usize) -> Vec<f32> {
+    cells
+        .iter()
+        .map(|cell| match cell {
+            Some(owner) if *owner == player => 1.0,
+            Some(_) => -1.0,
+            None => 0.0,
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct TicTacToe {
+    pub cells: [Option<usize>; 9],
+    moves_made: usize,
+}
+
+impl BoardGame for TicTacToe {
+    const CELLS: u16 = 9;
+    const MOVES: u16 = 9;
+
+    fn new() -> Self {
+        TicTacToe {
+            cells: [None; 9],
+            moves_made: 0,
+        }
+    }
+
+    fn to_move(&self) -> usize {
+        self.moves_made % 2
+    }
+
+    fn legal_moves(&self) -> Vec<usize> {
+        if self.winner().is_some() {
+            return Vec::new();
+        }
+        (0..9).filter(|&cell| self.cells[cell].is_none()).collect()
+    }
+
+    fn play(&mut self, mv: usize) {
+        assert!(self.cells[mv].is_none(), "Cell {} is already taken", mv);
+        self.cells[mv] = Some(self.to_move());
+        self.moves_made += 1;
+    }
+
+    fn winner(&self) -> Option<usize> {
+        const LINES: [[usize; 3]; 8] = [
+            [0, 1, 2], [3, 4, 5], [6, 7, 8],
+            [0, 3, 6], [1, 4, 7], [2, 5, 8],
+            [0, 4, 8], [2, 4, 6],
+        ];
+        LINES.iter().find_map(|line| {
+            let owner = self.cells[line[0]]?;
+            line.iter().all(|&cell| self.cells[cell] == Some(owner)).then_some(owner)
+        })
+    }
+
+    fn encode(&self, player: usize) -> Vec<f32> {
+        encode_cells(&self.cells, player)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ConnectFour {
+    /// Row-major from the bottom row up, 7 columns by 6 rows
+    pub cells: [Option<usize>; 42],
+    moves_made: usize,
+}
+
+impl ConnectFour {
+    pub const COLUMNS: usize = 7;
+    pub const ROWS: usize = 6;
+
+    fn cell(&self, column: isize, row: isize) -> Option<usize> {
+        if column < 0 || row < 0 || column >= Self::COLUMNS as isize || row >= Self::ROWS as isize {
+            return None;
+        }
+        self.cells[row as usize * Self::COLUMNS + column as usize]
+    }
+}
+
+impl BoardGame for ConnectFour {
+    const CELLS: u16 = 42;
+    const MOVES: u16 = 7;
+
+    fn new() -> Self {
+        ConnectFour {
+            cells: [None; 42],
+            moves_made: 0,
+        }
+    }
+
+    fn to_move(&self) -> usize {
+        self.moves_made % 2
+    }
+
+    fn legal_moves(&self) -> Vec<usize> {
+        if self.winner().is_some() {
+            return Vec::new();
+        }
+        (0..Self::COLUMNS)
+            .filter(|&column| self.cells[(Self::ROWS - 1) * Self::COLUMNS + column].is_none())
+            .collect()
+    }
+
+    fn play(&mut self, mv: usize) {
+        let row = (0..Self::ROWS)
+            .find(|row| self.cells[row * Self::COLUMNS + mv].is_none())
+            .expect("Column is full");
+        self.cells[row * Self::COLUMNS + mv] = Some(self.to_move());
+        self.moves_made += 1;
+    }
+
+    fn winner(&self) -> Option<usize> {
+        const DIRECTIONS: [(isize, isize); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+        for row in 0..Self::ROWS as isize {
+            for column in 0..Self::COLUMNS as isize {
+                let Some(owner) = self.cell(column, row) else { continue };
+                for (dc, dr) in DIRECTIONS {
+                    if (1..4).all(|i| self.cell(column + dc * i, row + dr * i) == Some(owner)) {
+                        return Some(owner);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn encode(&self, player: usize) -> Vec<f32> {
+        encode_cells(&self.cells, player)
+    }
+}
+
+/// Picks the legal move with the highest network output, ties going to the lowest move
+pub fn choose_move<G: BoardGame>(game: &G, network: &mut Network) -> usize {
+    let outputs = network.activate(&game.encode(game.to_move()));
+    let moves = game.legal_moves();
+    moves
+        .iter()
+        .copied()
+        .fold(moves[0], |best, mv| if outputs[mv] > outputs[best] { mv } else { best })
+}
+
+/// Plays one game and returns the score of each player: 1.0 win, 0.5 draw, 0.0 loss
+pub fn play_game<G: BoardGame>(first: &mut Network, second: &mut Network) -> [f32; 2] {
+    for network in [&mut *first, &mut *second] {
+        assert_eq!(network.input_count(), G::CELLS as usize, "Players need {} inputs", G::CELLS);
+        assert_eq!(network.output_count(), G::MOVES as usize, "Players need {} outputs", G::MOVES);
+        network.reset();
+    }
+
+    let mut game = G::new();
+    while !game.is_over() {
+        let mv = if game.to_move() == 0 {
+            choose_move(&game, first)
+        } else {
+            choose_move(&game, second)
+        };
+        game.play(mv);
+    }
+
+    match game.winner() {
+        Some(0) => [1.0, 0.0],
+        Some(_) => [0.0, 1.0],
+        None => [0.5, 0.5],
+    }
+}
+
+/// Plays two games, one with each side moving first, and returns the summed scores
+pub fn play_match<G: BoardGame>(a: &mut Network, b: &mut Network) -> [f32; 2] {
+    let [a_first, b_second] = play_game::<G>(a, b);
+    let [b_first, a_second] = play_game::<G>(b, a);
+    [a_first + a_second, b_first + b_second]
+}
+
+/// Every network plays a match against every other one.
+/// Returns each network's mean score per game, in [0, 1].
+pub fn round_robin<G: BoardGame>(networks: &mut [Network]) -> Vec<f32> {
+    let mut scores = vec![0.0; networks.len()];
+    for i in 0..networks.len() {
+        for j in i + 1..networks.len() {
+            let (left, right) = networks.split_at_mut(j);
+            let [score_i, score_j] = play_match::<G>(&mut left[i], &mut right[0]);
+            scores[i] += score_i;
+            scores[j] += score_j;
+        }
+    }
+    let games = 2 * networks.len().saturating_sub(1);
+    scores
+        .iter()
+        .map(|score| if games == 0 { 0.0 } else { score / games as f32 })
+        .collect()
+}
+
+/// Mean score per game of one network playing a match against each opponent, in [0, 1]
+pub fn score_against<G: BoardGame>(network: &mut Network, opponents: &mut [Network]) -> f32 {
+    if opponents.is_empty() {
+        return 0.0;
+    }
+    let total: f32 = opponents
+        .iter_mut()
+        .map(|opponent| play_match::<G>(network, opponent)[0])
+        .sum();
+    total / (2 * opponents.len()) as f32
+}
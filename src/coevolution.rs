@@ -0,0 +1,117 @@
+use crate::{round_robin, score_against, BoardGame, Genome, Network};
+use rand::prelude::*;
+
+/// Past champions kept as fixed opponents for competitive coevolution.
+/// When full, the oldest champion is dropped.
+#[derive(Debug, Clone)]
+pub struct HallOfFame {
+    pub members: Vec<Genome>,
+    pub capacity: usize,
+}
+
+impl HallOfFame {
+    pub fn new(capacity: usize) -> Self {
+        HallOfFame {
+            members: Vec::new(),
+            capacity,
+        }
+    }
+
+    pub fn add(&mut self, champion: Genome) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.members.len() == self.capacity {
+            self.members.remove(0);
+        }
+        self.members.push(champion);
+    }
+
+    /// Samples up to `count` distinct members as networks
+    pub fn sample<R: Rng>(&self, count: usize, rng: &mut R) -> Vec<Network> {
+        self.members
+            .choose_multiple(rng, count)
+            .map(|genome| Network::from_genome(genome.clone()))
+            .collect()
+    }
+
+    /// All members as networks, oldest first
+    pub fn networks(&self) -> Vec<Network> {
+        self.members
+            .iter()
+            .map(|genome| Network::from_genome(genome.clone()))
+            .collect()
+    }
+}
+
+/// Relative fitness of one generation
+#[derive(Debug, Clone)]
+pub struct CoevolutionRecord {
+    pub generation: usize,
+    /// Index of the champion in the population
+    pub champion: usize,
+    /// Champion's mean score in the within-population tournament
+    pub champion_tournament_score: f32,
+    pub mean_tournament_score: f32,
+    /// Champion's mean score against the hall of fame before it was added, None for the first generation
+    pub champion_vs_hall_of_fame: Option<f32>,
+}
+
+/// Drives competitive coevolution: scores a population by round-robin tournament,
+/// measures the champion against past champions and keeps the history.
+#[derive(Debug, Clone)]
+pub struct Coevolution {
+    pub hall_of_fame: HallOfFame,
+    pub history: Vec<CoevolutionRecord>,
+}
+
+impl Coevolution {
+    pub fn new(hall_of_fame_capacity: usize) -> Self {
+        Coevolution {
+            hall_of_fame: HallOfFame::new(hall_of_fame_capacity),
+            history: Vec::new(),
+        }
+    }
+
+    /// Fitness of each genome as its mean round-robin score within the population
+    pub fn tournament_fitness<G: BoardGame>(&self, population: &[Genome]) -> Vec<f32> {
+        let mut networks: Vec<Network> = population
+            .iter()
+            .map(|genome| Network::from_genome(genome.clone()))
+            .collect();
+        round_robin::<G>(&mut networks)
+    }
+
+    /// Fitness of each genome as its mean score against `opponents` members sampled from the hall of fame
+    pub fn hall_of_fame_fitness<G: BoardGame, R: Rng>(&self, population: &[Genome], opponents: usize, rng: &mut R) -> Vec<f32> {
+        let mut sampled = self.hall_of_fame.sample(opponents, rng);
+        population
+            .iter()
+            .map(|genome| score_against::<G>(&mut Network::from_genome(genome.clone()), &mut sampled))
+            .collect()
+    }
+
+    /// Runs the tournament for a generation, records the champion's relative fitness and adds it
+    /// to the hall of fame. Returns the tournament fitness of each genome.
+    pub fn record_generation<G: BoardGame>(&mut self, generation: usize, population: &[Genome]) -> Vec<f32> {
+        let fitness = self.tournament_fitness::<G>(population);
+        let Some(champion) = (0..fitness.len()).max_by(|&a, &b| fitness[a].total_cmp(&fitness[b])) else {
+            return fitness;
+        };
+
+        let champion_vs_hall_of_fame = (!self.hall_of_fame.members.is_empty()).then(|| {
+            let mut network = Network::from_genome(population[champion].clone());
+            score_against::<G>(&mut network, &mut self.hall_of_fame.networks())
+        });
+
+        self.history.push(CoevolutionRecord {
+            generation,
+            champion,
+            champion_tournament_score: fitness[champion],
+            mean_tournament_score: fitness.iter().sum::<f32>() / fitness.len() as f32,
+            champion_vs_hall_of_fame,
+        });
+        self.hall_of_fame.add(population[champion].clone());
+        fitness
+    }
+}
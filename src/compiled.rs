@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use crate::{convert_f32_to_id, get_neuron_height, GeneType, Genome, Network, SynapseStatus};
+
+/// Flat execution plan of a genome for fast repeated evaluation.
+///
+/// Neurons get dense indices in the same order `Network::activate` updates them (inputs, hidden
+/// by height, outputs), so input `i` is neuron `i`. Incoming enabled synapses are stored as CSR:
+/// the sources and weights feeding neuron `n` are `sources[offsets[n]..offsets[n + 1]]` and the
+/// matching slice of `weights`. Evaluation semantics match `Network::activate` exactly.
+#[derive(Debug, Clone)]
+pub struct CompiledNetwork {
+    pub ids: Vec<[u8; 2]>,
+    pub biases: Vec<f32>,
+    pub offsets: Vec<usize>,
+    pub sources: Vec<usize>,
+    pub weights: Vec<f32>,
+    /// Neuron index of each output, ordered by output index
+    pub outputs: Vec<usize>,
+    pub input_count: usize,
+    /// Current activation of each neuron, carried between `activate` calls.
+    /// Starts from the genome's stored activations like `Network::from_genome`.
+    pub activations: Vec<f32>,
+}
+
+impl CompiledNetwork {
+    /// Compiles a genome in one pass over its genes
+    pub fn from_genome(genome: &Genome) -> Self {
+        // Id, type, bias and initial activation of each neuron
+        let mut neurons: Vec<([u8; 2], GeneType, f32, f32)> = Vec::new();
+        let mut synapses: Vec<([u8; 2], [u8; 2], f32)> = Vec::new();
+        for gene in &genome.genes {
+            match GeneType::from_u8(gene.flag[0]) {
+                Some(GeneType::Synapse) if gene.flag[1] == SynapseStatus::Enabled as u8 => {
+                    synapses.push((gene.id, convert_f32_to_id(gene.local_data), gene.extern_data));
+                }
+                Some(GeneType::Synapse) | None => {}
+                Some(gene_type) => neurons.push((gene.id, gene_type, gene.local_data, gene.extern_data)),
+            }
+        }
+
+        neurons.sort_by_key(|&(id, gene_type, _, _)| {
+            let rank = match gene_type {
+                GeneType::Input => 0,
+                GeneType::Output => 2,
+                _ => 1,
+            };
+            (rank, get_neuron_height(id[1]), id)
+        });
+        let index: HashMap<[u8; 2], usize> = neurons.iter().enumerate().map(|(i, (id, _, _, _))| (*id, i)).collect();
+
+        let mut incoming: Vec<Vec<(usize, f32)>> = vec![Vec::new(); neurons.len()];
+        for (from, to, weight) in synapses {
+            if let (Some(&from), Some(&to)) = (index.get(&from), index.get(&to)) {
+                incoming[to].push((from, weight));
+            }
+        }
+        // Same summation order as `Network::activate`
+        for edges in incoming.iter_mut() {
+            edges.sort_by_key(|&(from, _)| neurons[from].0);
+        }
+        let mut offsets = Vec::with_capacity(neurons.len() + 1);
+        let mut sources = Vec::new();
+        let mut weights = Vec::new();
+        offsets.push(0);
+        for edges in incoming {
+            for (from, weight) in edges {
+                sources.push(from);
+                weights.push(weight);
+            }
+            offsets.push(sources.len());
+        }
+
+        let input_count = neurons.iter().filter(|(_, gene_type, _, _)| *gene_type == GeneType::Input).count();
+        let output_count = neurons.iter().filter(|(_, gene_type, _, _)| *gene_type == GeneType::Output).count();
+        let outputs = (0..output_count).map(|output| index[&[255 - output as u8, 0]]).collect();
+
+        CompiledNetwork {
+            ids: neurons.iter().map(|(id, _, _, _)| *id).collect(),
+            biases: neurons.iter().map(|(_, _, bias, _)| *bias).collect(),
+            offsets,
+            sources,
+            weights,
+            outputs,
+            input_count,
+            activations: neurons.iter().map(|(_, _, _, activation)| *activation).collect(),
+        }
+    }
+
+    pub fn output_count(&self) -> usize {
+        self.outputs.len()
+    }
+
+    /// Runs one step, see `Network::activate`
+    pub fn activate(&mut self, inputs: &[f32]) -> Vec<f32> {
+        assert_eq!(inputs.len(), self.input_count, "Expected {} inputs, got {}", self.input_count, inputs.len());
+        self.activations[..self.input_count].copy_from_slice(inputs);
+        for neuron in self.input_count..self.ids.len() {
+            let mut sum = 0.0;
+            for edge in self.offsets[neuron]..self.offsets[neuron + 1] {
+                sum += self.weights[edge] * self.activations[self.sources[edge]];
+            }
+            self.activations[neuron] = (self.biases[neuron] + sum).tanh();
+        }
+        self.outputs.iter().map(|&neuron| self.activations[neuron]).collect()
+    }
+
+    /// Clears all activations
+    pub fn reset(&mut self) {
+        self.activations.iter_mut().for_each(|activation| *activation = 0.0);
+    }
+
+    /// Runs every row from a reset state without touching the carried activations, see `Network::activate_batch`
+    pub fn activate_batch<R: AsRef<[f32]>>(&self, inputs: &[R]) -> Vec<Vec<f32>> {
+        let mut scratch = self.clone();
+        inputs
+            .iter()
+            .map(|row| {
+                scratch.reset();
+                scratch.activate(row.as_ref())
+            })
+            .collect()
+    }
+}
+
+impl Network {
+    /// Compiles the network's genome into a flat execution plan
+    pub fn compile(&self) -> CompiledNetwork {
+        CompiledNetwork::from_genome(&self.genome)
+    }
+}
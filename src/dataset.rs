@@ -0,0 +1,377 @@
+use std::fmt;
+use std::path::Path;
+
+use crate::{Genome, Network};
+use rand::prelude::*;
+use rand::rngs::StdRng;
+
+#[derive(Debug)]
+pub enum DatasetError {
+    Io(std::io::Error),
+    Empty,
+    RaggedRow { line: usize, expected: usize, found: usize },
+    NotANumber { line: usize, column: usize, value: String },
+    MissingTargetColumn(String),
+    ShapeMismatch { inputs: usize, outputs: usize, expected_inputs: usize, expected_outputs: usize },
+    InvalidFoldCount { k: usize, rows: usize },
+}
+
+impl fmt::Display for DatasetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DatasetError::Io(err) => write!(f, "could not read dataset: {}", err),
+            DatasetError::Empty => write!(f, "dataset has no rows"),
+            DatasetError::RaggedRow { line, expected, found } => {
+                write!(f, "line {} has {} fields, expected {}", line, found, expected)
+            }
+            DatasetError::NotANumber { line, column, value } => {
+                write!(f, "value {:?} at line {}, column {} is not a number", value, line, column)
+            }
+            DatasetError::MissingTargetColumn(column) => write!(f, "target column {} not found", column),
+            DatasetError::ShapeMismatch { inputs, outputs, expected_inputs, expected_outputs } => write!(
+                f,
+                "network has {} inputs and {} outputs, dataset needs {} and {}",
+                inputs, outputs, expected_inputs, expected_outputs
+            ),
+            DatasetError::InvalidFoldCount { k, rows } => {
+                write!(f, "cannot make {} folds from {} rows, k must be between 2 and the number of rows", k, rows)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DatasetError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TargetColumn {
+    Last,
+    Index(usize),
+    Name(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetKind {
+    /// The target column is a number and becomes a single output
+    Regression,
+    /// The target column is a class label and becomes one one-hot output per class
+    Classification,
+}
+
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    pub delimiter: char,
+    /// None detects a header by checking whether the first row's features parse as numbers
+    pub has_header: Option<bool>,
+    pub target: TargetColumn,
+    pub kind: TargetKind,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: ',',
+            has_header: None,
+            target: TargetColumn::Last,
+            kind: TargetKind::Classification,
+        }
+    }
+}
+
+/// Rows of numeric features with their targets, ready to feed to a `Network`
+#[derive(Debug, Clone)]
+pub struct Dataset {
+    pub feature_names: Vec<String>,
+    pub features: Vec<Vec<f32>>,
+    pub targets: Vec<Vec<f32>>,
+    /// Class label of each one-hot output in order of first appearance, empty for regression
+    pub class_names: Vec<String>,
+}
+
+/// Z-score normalization fitted on one dataset and applied to others
+#[derive(Debug, Clone)]
+pub struct Normalizer {
+    pub means: Vec<f32>,
+    pub std_devs: Vec<f32>,
+}
+
+/// Train, validation and test subsets of a dataset
+#[derive(Debug, Clone)]
+pub struct DatasetSplits {
+    pub train: Dataset,
+    pub validation: Dataset,
+    pub test: Dataset,
+}
+
+impl Dataset {
+    /// Loads a numeric CSV file
+    pub fn load_csv<P: AsRef<Path>>(path: P, options: &CsvOptions) -> Result<Self, DatasetError> {
+        let text = std::fs::read_to_string(path).map_err(DatasetError::Io)?;
+        Self::parse_csv(&text, options)
+    }
+
+    /// Parses CSV text. Blank lines are skipped and fields may be wrapped in double quotes.
+    pub fn parse_csv(text: &str, options: &CsvOptions) -> Result<Self, DatasetError> {
+        let rows: Vec<(usize, Vec<String>)> = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                let fields = line
+                    .split(options.delimiter)
+                    .map(|field| field.trim().trim_matches('"').to_string())
+                    .collect();
+                (index + 1, fields)
+            })
+            .collect();
+        let Some((_, first)) = rows.first() else {
+            return Err(DatasetError::Empty);
+        };
+        let width = first.len();
+
+        let target = match &options.target {
+            TargetColumn::Last => width - 1,
+            TargetColumn::Index(index) if *index < width => *index,
+            TargetColumn::Index(index) => return Err(DatasetError::MissingTargetColumn(index.to_string())),
+            TargetColumn::Name(name) => first
+                .iter()
+                .position(|field| field == name)
+                .ok_or_else(|| DatasetError::MissingTargetColumn(name.clone()))?,
+        };
+
+        let has_header = options.has_header.unwrap_or_else(|| {
+            matches!(options.target, TargetColumn::Name(_))
+                || first
+                    .iter()
+                    .enumerate()
+                    .any(|(column, field)| column != target && field.parse::<f32>().is_err())
+        });
+        let (feature_names, body) = if has_header {
+            let names = first
+                .iter()
+                .enumerate()
+                .filter(|(column, _)| *column != target)
+                .map(|(_, name)| name.clone())
+                .collect();
+            (names, &rows[1..])
+        } else {
+            let names = (0..width).filter(|&column| column != target).map(|column| format!("x{}", column)).collect();
+            (names, &rows[..])
+        };
+        if body.is_empty() {
+            return Err(DatasetError::Empty);
+        }
+
+        let mut features = Vec::with_capacity(body.len());
+        let mut labels = Vec::with_capacity(body.len());
+        for (line, fields) in body {
+            if fields.len() != width {
+                return Err(DatasetError::RaggedRow { line: *line, expected: width, found: fields.len() });
+            }
+            let mut row = Vec::with_capacity(width - 1);
+            for (column, field) in fields.iter().enumerate() {
+                if column == target {
+                    continue;
+                }
+                row.push(field.parse::<f32>().map_err(|_| DatasetError::NotANumber {
+                    line: *line,
+                    column: column + 1,
+                    value: field.clone(),
+                })?);
+            }
+            features.push(row);
+            labels.push((*line, target + 1, fields[target].clone()));
+        }
+
+        let (targets, class_names) = match options.kind {
+            TargetKind::Regression => {
+                let targets = labels
+                    .into_iter()
+                    .map(|(line, column, value)| {
+                        value
+                            .parse::<f32>()
+                            .map(|number| vec![number])
+                            .map_err(|_| DatasetError::NotANumber { line, column, value })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                (targets, Vec::new())
+            }
+            TargetKind::Classification => {
+                let mut class_names: Vec<String> = Vec::new();
+                for (_, _, value) in &labels {
+                    if !class_names.contains(value) {
+                        class_names.push(value.clone());
+                    }
+                }
+                let targets = labels
+                    .iter()
+                    .map(|(_, _, value)| {
+                        class_names
+                            .iter()
+                            .map(|class| if class == value { 1.0 } else { 0.0 })
+                            .collect()
+                    })
+                    .collect();
+                (targets, class_names)
+            }
+        };
+
+        Ok(Dataset {
+            feature_names,
+            features,
+            targets,
+            class_names,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.features.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.features.is_empty()
+    }
+
+    /// Number of network inputs the dataset needs
+    pub fn input_count(&self) -> usize {
+        self.feature_names.len()
+    }
+
+    /// Number of network outputs the dataset needs
+    pub fn output_count(&self) -> usize {
+        self.targets.first().map_or(0, |target| target.len())
+    }
+
+    /// Creates an empty network with the inputs and outputs the dataset needs
+    pub fn new_network(&self) -> Network {
+        Network::from_genome(Genome::new(self.input_count() as u16, self.output_count() as u16))
+    }
+
+    /// Checks that a network has one input per feature and one output per target
+    pub fn check_network(&self, network: &Network) -> Result<(), DatasetError> {
+        let (inputs, outputs) = (network.input_count(), network.output_count());
+        if inputs != self.input_count() || outputs != self.output_count() {
+            return Err(DatasetError::ShapeMismatch {
+                inputs,
+                outputs,
+                expected_inputs: self.input_count(),
+                expected_outputs: self.output_count(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks that a genome's input and output neurons match the dataset
+    pub fn check_genome(&self, genome: &Genome) -> Result<(), DatasetError> {
+        self.check_network(&Network::from_genome(genome.clone()))
+    }
+
+    /// Runs every row through the network from a reset state
+    pub fn predict(&self, network: &mut Network) -> Result<Vec<Vec<f32>>, DatasetError> {
+        self.check_network(network)?;
+        Ok(network.activate_batch(&self.features))
+    }
+
+    /// Keeps the rows at the given indices, in that order
+    pub fn subset(&self, indices: &[usize]) -> Dataset {
+        Dataset {
+            feature_names: self.feature_names.clone(),
+            features: indices.iter().map(|&i| self.features[i].clone()).collect(),
+            targets: indices.iter().map(|&i| self.targets[i].clone()).collect(),
+            class_names: self.class_names.clone(),
+        }
+    }
+
+    /// Shuffles the rows with a seed and splits them into train, validation and test.
+    /// Whatever is left after the train and validation fractions becomes the test set.
+    pub fn split(&self, train_fraction: f32, validation_fraction: f32, seed: u64) -> DatasetSplits {
+        assert!(train_fraction + validation_fraction <= 1.0, "Train and validation fractions must sum to at most 1");
+        let indices = self.shuffled_indices(seed);
+        let train_end = (self.len() as f32 * train_fraction).round() as usize;
+        let validation_end = (train_end + (self.len() as f32 * validation_fraction).round() as usize).min(self.len());
+
+        DatasetSplits {
+            train: self.subset(&indices[..train_end]),
+            validation: self.subset(&indices[train_end..validation_end]),
+            test: self.subset(&indices[validation_end..]),
+        }
+    }
+
+    /// Shuffles the rows with a seed and returns k (train, held-out) pairs, each row held out once
+    pub fn k_folds(&self, k: usize, seed: u64) -> Result<Vec<(Dataset, Dataset)>, DatasetError> {
+        if k < 2 || k > self.len() {
+            return Err(DatasetError::InvalidFoldCount { k, rows: self.len() });
+        }
+        let indices = self.shuffled_indices(seed);
+        Ok((0..k)
+            .map(|fold| {
+                let start = fold * self.len() / k;
+                let end = (fold + 1) * self.len() / k;
+                let train: Vec<usize> = indices[..start].iter().chain(&indices[end..]).copied().collect();
+                (self.subset(&train), self.subset(&indices[start..end]))
+            })
+            .collect())
+    }
+
+    /// Mean fitness of a genome over k held-out folds. Each fold is normalized with statistics
+    /// fitted on the remaining folds before `fitness` scores the network on it.
+    pub fn cross_validate<F>(&self, genome: &Genome, k: usize, seed: u64, fitness: F) -> Result<f32, DatasetError>
+    where
+        F: Fn(&mut Network, &Dataset) -> f32,
+    {
+        self.check_genome(genome)?;
+        let mut network = Network::from_genome(genome.clone());
+        let folds = self.k_folds(k, seed)?;
+        let total: f32 = folds
+            .into_iter()
+            .map(|(train, mut held_out)| {
+                Normalizer::fit(&train).apply(&mut held_out);
+                fitness(&mut network, &held_out)
+            })
+            .sum();
+        Ok(total / k as f32)
+    }
+
+    fn shuffled_indices(&self, seed: u64) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..self.len()).collect();
+        indices.shuffle(&mut StdRng::seed_from_u64(seed));
+        indices
+    }
+}
+
+impl Normalizer {
+    /// Fits per-feature mean and standard deviation
+    pub fn fit(dataset: &Dataset) -> Self {
+        let count = dataset.len().max(1) as f32;
+        let means: Vec<f32> = (0..dataset.input_count())
+            .map(|column| dataset.features.iter().map(|row| row[column]).sum::<f32>() / count)
+            .collect();
+        let std_devs = means
+            .iter()
+            .enumerate()
+            .map(|(column, mean)| {
+                let variance = dataset.features.iter().map(|row| (row[column] - mean).powi(2)).sum::<f32>() / count;
+                if variance > 0.0 { variance.sqrt() } else { 1.0 }
+            })
+            .collect();
+        Normalizer { means, std_devs }
+    }
+
+    pub fn apply(&self, dataset: &mut Dataset) {
+        for row in dataset.features.iter_mut() {
+            for (column, value) in row.iter_mut().enumerate() {
+                *value = (*value - self.means[column]) / self.std_devs[column];
+            }
+        }
+    }
+}
+
+impl DatasetSplits {
+    /// Fits a normalizer on the training split and applies it to all three splits
+    pub fn normalize(&mut self) -> Normalizer {
+        let normalizer = Normalizer::fit(&self.train);
+        normalizer.apply(&mut self.train);
+        normalizer.apply(&mut self.validation);
+        normalizer.apply(&mut self.test);
+        normalizer
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::BTreeMap;
+
+use crate::{convert_f32_to_id, get_neuron_height, GeneType, Genome, Network, SynapseStatus};
+
+/// Neuron as drawn: id, type and label lines
+type DotNode = ([u8; 2], Option<GeneType>, String);
+/// Synapse as drawn: source, destination, weight, enabled
+type DotEdge = ([u8; 2], [u8; 2], f32, bool);
+
+// Graphviz export for headless rendering
+impl Genome {
+    /// Graphviz DOT source of the genome.
+    /// Each input's tree is a cluster with one rank per `get_neuron_height`, outputs share the bottom rank,
+    /// edge thickness follows |weight| (blue positive, red negative) and disabled synapses are dashed.
+    pub fn to_dot(&self) -> String {
+        let mut nodes = Vec::new();
+        let mut edges = Vec::new();
+        for gene in &self.genes {
+            match GeneType::from_u8(gene.flag[0]) {
+                Some(GeneType::Synapse) => edges.push((
+                    gene.id,
+                    convert_f32_to_id(gene.local_data),
+                    gene.extern_data,
+                    gene.flag[1] == SynapseStatus::Enabled as u8,
+                )),
+                gene_type => nodes.push((gene.id, gene_type, format!("bias {}", gene.local_data))),
+            }
+        }
+        render_dot("genome", nodes, edges)
+    }
+}
+
+impl Network {
+    /// Graphviz DOT source of the network in the same layout as `Genome::to_dot`, labelled with current activations
+    pub fn to_dot(&self) -> String {
+        let nodes = self
+            .neurons
+            .values()
+            .map(|neuron| {
+                let label = format!("bias {}\\nact {:.3}", neuron.bias, neuron.activation);
+                (neuron.id, GeneType::from_u8(neuron.flag[0]), label)
+            })
+            .collect();
+        let edges = self
+            .synapses
+            .values()
+            .map(|synapse| {
+                let enabled = synapse.flag[1] == SynapseStatus::Enabled as u8;
+                (synapse.id, synapse.destination, synapse.weight, enabled)
+            })
+            .collect();
+        render_dot("network", nodes, edges)
+    }
+}
+
+fn node_name(id: [u8; 2]) -> String {
+    format!("n{}_{}", id[0], id[1])
+}
+
+fn render_dot(name: &str, mut nodes: Vec<DotNode>, mut edges: Vec<DotEdge>) -> String {
+    nodes.sort_by_key(|(id, _, _)| *id);
+    edges.sort_by_key(|&(from, to, _, _)| (from, to));
+
+    let mut trees: BTreeMap<u8, BTreeMap<u32, Vec<&DotNode>>> = BTreeMap::new();
+    let mut outputs = Vec::new();
+    for node in &nodes {
+        match node.1 {
+            Some(GeneType::Output) => outputs.push(node),
+            _ => trees.entry(node.0[0]).or_default().entry(get_neuron_height(node.0[1])).or_default().push(node),
+        }
+    }
+    let node_line = |(id, gene_type, label): &DotNode| {
+        let (kind, shape, fill) = match gene_type {
+            Some(GeneType::Input) => ("input", "box", "#cfe8cf"),
+            Some(GeneType::Output) => ("output", "doublecircle", "#f6d3b3"),
+            _ => ("hidden", "circle", "#dde4f0"),
+        };
+        format!(
+            "{} [label=\"{} [{}, {}]\\n{}\", shape={}, style=filled, fillcolor=\"{}\"];",
+            node_name(*id), kind, id[0], id[1], label, shape, fill
+        )
+    };
+
+    let mut dot = format!("digraph {} {{\n    rankdir=TB;\n    node [fontname=\"Helvetica\", fontsize=10];\n", name);
+    for (tree, heights) in &trees {
+        dot += &format!("    subgraph cluster_tree_{} {{\n        label=\"tree {}\";\n        color=\"#bbbbbb\";\n", tree, tree);
+        for nodes in heights.values() {
+            for node in nodes {
+                dot += &format!("        {}\n", node_line(node));
+            }
+            if nodes.len() > 1 {
+                let names: Vec<String> = nodes.iter().map(|(id, _, _)| node_name(*id)).collect();
+                dot += &format!("        {{ rank=same; {}; }}\n", names.join("; "));
+            }
+        }
+        dot += "    }\n";
+    }
+    if !outputs.is_empty() {
+        for node in &outputs {
+            dot += &format!("    {}\n", node_line(node));
+        }
+        let names: Vec<String> = outputs.iter().map(|(id, _, _)| node_name(*id)).collect();
+        dot += &format!("    {{ rank=sink; {}; }}\n", names.join("; "));
+    }
+
+    let max_weight = edges.iter().map(|edge| edge.2.abs()).fold(0.0f32, f32::max);
+    for (from, to, weight, enabled) in &edges {
+        let penwidth = if max_weight > 0.0 { 0.5 + 3.5 * weight.abs() / max_weight } else { 1.0 };
+        let (color, style) = match (enabled, *weight >= 0.0) {
+            (false, _) => ("#999999", "dashed"),
+            (true, true) => ("#2b6cb0", "solid"),
+            (true, false) => ("#c53030", "solid"),
+        };
+        dot += &format!(
+            "    {} -> {} [label=\"{:.3}\", penwidth={:.2}, color=\"{}\", style={}];\n",
+            node_name(*from), node_name(*to), weight, penwidth, color, style
+        );
+    }
+    dot += "}\n";
+    dot
+}
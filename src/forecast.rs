@@ -0,0 +1,228 @@
+use std::ops::Range;
+
+use crate::{Genome, Network};
+use rand::prelude::*;
+use rand::rngs::StdRng;
+
+// SERIES GENERATORS
+
+/// Sum of sine waves, each given as (amplitude, period in steps, phase in radians)
+pub fn sine_mixture(length: usize, components: &[(f32, f32, f32)]) -> Vec<f32> {
+    (0..length)
+        .map(|t| {
+            components
+                .iter()
+                .map(|&(amplitude, period, phase)| amplitude * (2.0 * std::f32::consts::PI * t as f32 / period + phase).sin())
+                .sum()
+        })
+        .collect()
+}
+
+/// Mackey-Glass delay differential equation (beta 0.2, gamma 0.1, n 10), sampled once per time unit.
+/// `tau` of 17 or more gives chaotic behaviour. The initial transient is discarded.
+pub fn mackey_glass(length: usize, tau: usize) -> Vec<f32> {
+    const SUBSTEPS: usize = 10;
+    const TRANSIENT: usize = 500;
+    let dt = 1.0 / SUBSTEPS as f64;
+    let delay = tau * SUBSTEPS;
+    let mut history = vec![1.2f64; delay + 1];
+    let mut series = Vec::with_capacity(length);
+
+    for step in 0..(length + TRANSIENT) * SUBSTEPS {
+        let current = history[history.len() - 1];
+        let delayed = history[history.len() - 1 - delay];
+        let next = current + dt * (0.2 * delayed / (1.0 + delayed.powi(10)) - 0.1 * current);
+        history.push(next);
+        if step % SUBSTEPS == 0 && step / SUBSTEPS >= TRANSIENT {
+            series.push(next as f32);
+        }
+    }
+    series
+}
+
+/// X component of the Lorenz attractor (sigma 10, rho 28, beta 8/3) integrated with RK4 at step `dt`.
+/// The initial transient is discarded.
+pub fn lorenz(length: usize, dt: f32) -> Vec<f32> {
+    const TRANSIENT: usize = 1000;
+    let derivative = |[x, y, z]: [f64; 3]| [10.0 * (y - x), x * (28.0 - z) - y, x * y - 8.0 / 3.0 * z];
+    let add = |a: [f64; 3], b: [f64; 3], scale: f64| [a[0] + b[0] * scale, a[1] + b[1] * scale, a[2] + b[2] * scale];
+    let dt = dt as f64;
+    let mut state = [1.0, 1.0, 1.0];
+    let mut series = Vec::with_capacity(length);
+
+    for step in 0..length + TRANSIENT {
+        let k1 = derivative(state);
+        let k2 = derivative(add(state, k1, dt / 2.0));
+        let k3 = derivative(add(state, k2, dt / 2.0));
+        let k4 = derivative(add(state, k3, dt));
+        for i in 0..3 {
+            state[i] += dt / 6.0 * (k1[i] + 2.0 * k2[i] + 2.0 * k3[i] + k4[i]);
+        }
+        if step >= TRANSIENT {
+            series.push(state[0] as f32);
+        }
+    }
+    series
+}
+
+/// Autoregressive process x[t] = sum(coefficients[i] * x[t - 1 - i]) + gaussian noise
+pub fn noisy_ar(length: usize, coefficients: &[f32], noise_std: f32, seed: u64) -> Vec<f32> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut series: Vec<f32> = Vec::with_capacity(length);
+    for t in 0..length {
+        let signal: f32 = coefficients
+            .iter()
+            .enumerate()
+            .filter(|(lag, _)| t > *lag)
+            .map(|(lag, coefficient)| coefficient * series[t - 1 - lag])
+            .sum();
+        series.push(signal + noise_std * gaussian(&mut rng));
+    }
+    series
+}
+
+/// Standard normal sample using the Box-Muller transform
+fn gaussian(rng: &mut StdRng) -> f32 {
+    let u1: f32 = rng.gen_range(f32::EPSILON..1.0);
+    let u2: f32 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f32::consts::PI * u2).cos()
+}
+
+// SUPERVISED EVALUATION
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForecastMode {
+    /// The network sees the last `window` values at once and is reset before every prediction
+    SlidingWindow,
+    /// The network sees one value per step and keeps its state across the whole segment
+    Recurrent,
+}
+
+/// Scores of a forecaster on one segment of a series
+#[derive(Debug, Clone)]
+pub struct ForecastScore {
+    pub mse: f32,
+    /// Root mean squared error divided by the standard deviation of the targets
+    pub nrmse: f32,
+    /// NRMSE of the persistence baseline, which predicts the last observed value
+    pub persistence_nrmse: f32,
+    pub samples: usize,
+}
+
+/// A one-step-input, `horizon`-step-ahead forecasting problem on a scaled series.
+/// Values before `split` are used for training, targets from `split` onward are held out.
+/// Scaling maps the training values to [-1, 1], so held-out values can fall outside it.
+#[derive(Debug, Clone)]
+pub struct ForecastTask {
+    pub series: Vec<f32>,
+    pub window: usize,
+    pub horizon: usize,
+    pub split: usize,
+}
+
+impl ForecastTask {
+    /// Holds out everything after `train_fraction` of the series and scales the training part to [-1, 1].
+    /// The window is at most 255 inputs, since input 255 would share its id with the first output.
+    pub fn new(series: Vec<f32>, window: usize, horizon: usize, train_fraction: f32) -> Self {
+        assert!((1..=255).contains(&window), "Window must be between 1 and 255");
+        assert!(horizon >= 1, "Horizon must be at least 1");
+        let split = ((series.len() as f32 * train_fraction) as usize).max(window + horizon).min(series.len());
+        // Fit the scaling on training values only so nothing leaks from the held-out segment
+        let min = series[..split].iter().copied().fold(f32::INFINITY, f32::min);
+        let max = series[..split].iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let range = if max > min { max - min } else { 1.0 };
+        let series: Vec<f32> = series.iter().map(|value| 2.0 * (value - min) / range - 1.0).collect();
+
+        ForecastTask {
+            series,
+            window,
+            horizon,
+            split,
+        }
+    }
+
+    /// Number of network inputs for a mode
+    pub fn input_count(&self, mode: ForecastMode) -> u16 {
+        match mode {
+            ForecastMode::SlidingWindow => self.window as u16,
+            ForecastMode::Recurrent => 1,
+        }
+    }
+
+    /// Creates an empty single-output network for a mode
+    pub fn new_network(&self, mode: ForecastMode) -> Network {
+        Network::from_genome(Genome::new(self.input_count(mode), 1))
+    }
+
+    /// Scores the network on the training segment
+    pub fn evaluate_train(&self, network: &mut Network, mode: ForecastMode) -> ForecastScore {
+        let end = self.split.saturating_sub(self.horizon);
+        self.evaluate_range(network, mode, self.window - 1..end)
+    }
+
+    /// Scores the network on the held-out segment
+    pub fn evaluate_test(&self, network: &mut Network, mode: ForecastMode) -> ForecastScore {
+        let end = self.series.len().saturating_sub(self.horizon);
+        self.evaluate_range(network, mode, self.split..end)
+    }
+
+    /// Scores predictions made at each time `t` in `range`, where the target is `series[t + horizon]`.
+    /// In recurrent mode the `window` values before the range are fed as unscored warmup.
+    fn evaluate_range(&self, network: &mut Network, mode: ForecastMode, range: Range<usize>) -> ForecastScore {
+        assert_eq!(network.input_count(), self.input_count(mode) as usize, "Forecaster needs {} inputs", self.input_count(mode));
+        assert_eq!(network.output_count(), 1, "Forecaster needs 1 output");
+
+        let mut predictions = Vec::with_capacity(range.len());
+        match mode {
+            ForecastMode::SlidingWindow => {
+                for t in range.clone() {
+                    network.reset();
+                    predictions.push(network.activate(&self.series[t + 1 - self.window..=t])[0]);
+                }
+            }
+            ForecastMode::Recurrent => {
+                network.reset();
+                for t in range.start.saturating_sub(self.window)..range.start {
+                    network.activate(&self.series[t..=t]);
+                }
+                for t in range.clone() {
+                    predictions.push(network.activate(&self.series[t..=t])[0]);
+                }
+            }
+        }
+
+        let targets: Vec<f32> = range.clone().map(|t| self.series[t + self.horizon]).collect();
+        let persistence: Vec<f32> = range.map(|t| self.series[t]).collect();
+        let mse = mean_squared_error(&predictions, &targets);
+        ForecastScore {
+            mse,
+            nrmse: normalized_rmse(mse, &targets),
+            persistence_nrmse: normalized_rmse(mean_squared_error(&persistence, &targets), &targets),
+            samples: targets.len(),
+        }
+    }
+}
+
+fn mean_squared_error(predictions: &[f32], targets: &[f32]) -> f32 {
+    if targets.is_empty() {
+        return 0.0;
+    }
+    predictions
+        .iter()
+        .zip(targets)
+        .map(|(prediction, target)| (prediction - target).powi(2))
+        .sum::<f32>()
+        / targets.len() as f32
+}
+
+fn normalized_rmse(mse: f32, targets: &[f32]) -> f32 {
+    if targets.is_empty() {
+        return 0.0;
+    }
+    let mean = targets.iter().sum::<f32>() / targets.len() as f32;
+    let variance = targets.iter().map(|target| (target - mean).powi(2)).sum::<f32>() / targets.len() as f32;
+    if variance == 0.0 {
+        return mse.sqrt();
+    }
+    (mse / variance).sqrt()
+}
@@ -1,9 +1,7 @@
-use std::collections::HashMap;
-
-use crate::{Gene, GeneType, Genome, SynapseStatus};
+use crate::{Gene, GeneType, Genome, Lineage, MutationOperator, SynapseStatus};
 use rand::prelude::*;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct MutationConfig {
     pub connection_bias: usize,
     pub inclusion_probability: f32,
@@ -51,6 +49,7 @@ impl Genome {
         Genome {
             genes,
             config: mutation_config,
+            lineage: Lineage::root(),
         }
     }
     
@@ -61,6 +60,7 @@ impl Genome {
             self.create_neuron(selected_pair);
             self.create_synapse(selected_pair);
             self.sort_genes();
+            self.lineage.mutations.push(MutationOperator::AddNeuron);
         }
     }
     
@@ -77,6 +77,7 @@ impl Genome {
                 self.create_synapse((selected_pair.1, target_id));
             }
             self.sort_genes();
+            self.lineage.mutations.push(MutationOperator::AddConnectedNeuron);
 
         }
     }
@@ -88,6 +89,25 @@ impl Genome {
             if let Some(target_id) = self.select_random(&possible_targets) {
                 self.create_synapse((source_id, target_id));
                 self.sort_genes();
+                self.lineage.mutations.push(MutationOperator::AddSynapse);
+            }
+        }
+    }
+
+    /// Creates a random recurrent synapse from a hidden neuron back to a hidden neuron that is updated before it.
+    /// The destination reads the source's activation from the previous network step.
+    pub fn random_recurrent_synapse(&mut self) {
+        let sources: Vec<[u8; 2]> = self.genes
+            .iter()
+            .filter(|gene| GeneType::from_u8(gene.flag[0]) == Some(GeneType::Hidden))
+            .map(|gene| gene.id)
+            .collect();
+        if let Some(source_id) = self.select_random(&sources) {
+            let possible_targets = self.get_recurrent_synapse_candidates(source_id);
+            if let Some(target_id) = self.select_random(&possible_targets) {
+                self.create_synapse((source_id, target_id));
+                self.sort_genes();
+                self.lineage.mutations.push(MutationOperator::AddRecurrentSynapse);
             }
         }
     }
@@ -96,6 +116,7 @@ impl Genome {
     pub fn disable_random_synapse(&mut self) {
         if let Some(index) = self.select_random_index(&self.get_synapse_indices(SynapseStatus::Enabled)) {
             self.genes[index].flag[1] = SynapseStatus::Disabled as u8;
+            self.lineage.mutations.push(MutationOperator::DisableSynapse);
             println!("Disabled synapse at index {}", index);
         } else {
             println!("No enabled synapses to disable.");
@@ -105,6 +126,7 @@ impl Genome {
     pub fn enable_random_synapse(&mut self) {
         if let Some(index) = self.select_random_index(&self.get_synapse_indices(SynapseStatus::Disabled)) {
             self.genes[index].flag[1] = SynapseStatus::Enabled as u8;
+            self.lineage.mutations.push(MutationOperator::EnableSynapse);
             println!("Enabled synapse at index {}", index);
         } else {
             println!("No disabled synapses to enable.");
@@ -124,7 +146,9 @@ impl Genome {
             }
         }
         if let Some(selected_pair) = self.select_random_pair(&source_target_ids) {
-            self.remove_synapse(selected_pair.0, selected_pair.1);
+            if self.remove_synapse(selected_pair.0, selected_pair.1) {
+                self.lineage.mutations.push(MutationOperator::RemoveSynapse);
+            }
         }
     }
     pub fn crossover(
@@ -164,7 +188,6 @@ impl Genome {
     pub fn remove_synapse(&mut self, from_id: [u8; 2], to_id: [u8; 2]) -> bool {
         if let Some(index) = self.find_synapse_index(from_id, to_id) {
             self.genes.remove(index);
-            self.update_parent_child_counts(from_id, to_id);
             self.remove_neuron_if_isolated(from_id);
             self.remove_neuron_if_isolated(to_id);
             println!("Removed synapse from {:?} to {:?}", from_id, to_id);
@@ -175,24 +198,15 @@ impl Genome {
         }
     }
 
-    /// Updates the child count of the parent neuron after synapse removal.
-    fn update_parent_child_counts(&mut self, from_id: [u8; 2], _to_id: [u8; 2]) {
-        if let Some((_, parent_gene)) = self.find_gene_by_id_and_type_mut(
-            from_id,
-            &[GeneType::Input, GeneType::Hidden],
-        ) {
-            if parent_gene.flag[1] > 0 {
-                parent_gene.flag[1] -= 1;
-            }
-        }
-    }
-
-    /// Removes a neuron if it has no incoming or outgoing synapses and is not an Input or Output neuron.
+    /// Removes a neuron if it has no incoming or outgoing synapses, no child neurons and is not an Input or Output neuron.
+    /// The parent's child count is lowered so it keeps matching the children present.
     fn remove_neuron_if_isolated(&mut self, neuron_id: [u8; 2]) {
-        if let Some(gene_type) = self.get_gene_type(neuron_id) {
-            if gene_type == GeneType::Input || gene_type == GeneType::Output {
-                return;
-            }
+        // A self-loop names the same neuron twice, and it may already be gone
+        let is_hidden = self.genes.iter().any(|gene| {
+            gene.id == neuron_id && GeneType::from_u8(gene.flag[0]) == Some(GeneType::Hidden)
+        });
+        if !is_hidden {
+            return;
         }
 
         let has_incoming = self.genes.iter().any(|gene| {
@@ -204,8 +218,17 @@ impl Genome {
             gene.id == neuron_id && GeneType::from_u8(gene.flag[0]) == Some(GeneType::Synapse)
         });
 
-        if !has_incoming && !has_outgoing {
+        let has_children = self.genes.iter().any(|gene| {
+            GeneType::from_u8(gene.flag[0]) == Some(GeneType::Hidden) && get_tree_parent(gene.id) == Some(neuron_id)
+        });
+
+        if !has_incoming && !has_outgoing && !has_children {
             self.genes.retain(|gene| gene.id != neuron_id);
+            if let Some(parent_id) = get_tree_parent(neuron_id) {
+                if let Some((_, parent_gene)) = self.find_gene_by_id_and_type_mut(parent_id, &[GeneType::Input, GeneType::Hidden]) {
+                    parent_gene.flag[1] = parent_gene.flag[1].saturating_sub(1);
+                }
+            }
             println!("Removed isolated neuron {:?}", neuron_id);
         }
     }
@@ -220,14 +243,6 @@ impl Genome {
         })
     }
 
-    /// Helper function to get the gene type of a neuron.
-    fn get_gene_type(&self, neuron_id: [u8; 2]) -> Option<GeneType> {
-        self.genes
-            .iter()
-            .find(|gene| gene.id == neuron_id)
-            .and_then(|gene| GeneType::from_u8(gene.flag[0]))
-    }
-
     /// Helper function to find a gene by its ID and type (mutable version).
     fn find_gene_by_id_and_type_mut(
         &mut self,
@@ -288,11 +303,10 @@ impl Genome {
             })
             .filter_map(|gene| {
                 let parent_id = gene.id;
-                let child_id = match gene.flag[1] {
-                    0 => [gene.id[0], 2 * gene.id[1] + 1],
-                    1 => [gene.id[0], 2 * gene.id[1] + 2],
-                    _ => return None,
-                };
+                // Removing a neuron frees its slot, which may be the first one while the second is taken
+                let child_id = [[gene.id[0], 2 * gene.id[1] + 1], [gene.id[0], 2 * gene.id[1] + 2]]
+                    .into_iter()
+                    .find(|child_id| !self.genes.iter().any(|gene| gene.id == *child_id))?;
                 Some((parent_id, child_id))
             })
             .collect();
@@ -318,7 +332,7 @@ impl Genome {
     pub fn get_synapse_candidates(&self, neuron_id: [u8; 2]) -> Vec<[u8; 2]> {
         let source_height = get_neuron_height(neuron_id[1]);
     
-        let mut candidates = self.genes
+        self.genes
             .iter()
             .filter(|gene| {
                 gene.id != neuron_id
@@ -327,9 +341,23 @@ impl Genome {
                     && (get_neuron_height(gene.id[1]) > source_height || gene.flag[0] == 3)
             })
             .map(|gene| gene.id)
-            .collect::<Vec<_>>();
-        
-        candidates
+            .collect::<Vec<_>>()
+    }
+
+    /// Finds hidden neurons updated no later than `neuron_id` (lower height, or same height and lower ID)
+    /// that a recurrent synapse from it could target
+    pub fn get_recurrent_synapse_candidates(&self, neuron_id: [u8; 2]) -> Vec<[u8; 2]> {
+        let source_height = get_neuron_height(neuron_id[1]);
+
+        self.genes
+            .iter()
+            .filter(|gene| {
+                GeneType::from_u8(gene.flag[0]) == Some(GeneType::Hidden)
+                    && (get_neuron_height(gene.id[1]), gene.id) <= (source_height, neuron_id)
+                    && self.find_synapse(neuron_id, gene.id).is_none()
+            })
+            .map(|gene| gene.id)
+            .collect()
     }
 
     /// Checks if two neurons are already connected via a synapse
@@ -340,7 +368,7 @@ impl Genome {
         if self.find_synapse(to_id, from_id).is_some() {
             return true;
         } 
-        return false; 
+        false
     }
 
     /// Selects a random element from a vector
@@ -349,6 +377,8 @@ impl Genome {
         items.choose(&mut rng).cloned()
     }
     
+    /// Selects a random element from the first `connection_bias` items of a vector
+    #[allow(dead_code)]
     fn select_random_weighted<T: Clone> (&self, items: &[T]) -> Option<T> {
         let mut rng = thread_rng();
         let limit = self.config.connection_bias.min(items.len());
@@ -415,6 +445,11 @@ pub fn convert_f32_to_id(value: f32) -> [u8; 2] {
     (value as u16).to_be_bytes()
 }
 
+/// Id of the neuron a hidden neuron hangs from in its tree, `None` for tree roots
+pub fn get_tree_parent(id: [u8; 2]) -> Option<[u8; 2]> {
+    id[1].checked_sub(1).map(|index| [id[0], index / 2])
+}
+
 /// Calculates the height of a neuron in the binary tree based on its position
 pub fn get_neuron_height(position: u8) -> u32 {
     let mut height = 0;
@@ -441,10 +476,10 @@ pub fn get_inorder_position(id1: u16) -> u16 {
 
     if is_left_child {
         // Left child: position is parent's position * 2
-        return parent_position * 2;
+        parent_position * 2
     } else {
         // Right child: position is parent's position * 2 + 1
-        return parent_position * 2 + 1;
+        parent_position * 2 + 1
     }
 }
 
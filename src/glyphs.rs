@@ -0,0 +1,196 @@
+use std::f32::consts::PI;
+
+use crate::Dataset;
+use rand::prelude::*;
+use rand::rngs::StdRng;
+
+/// Shapes the glyph generator can draw, each made of line strokes in a unit square (y down)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GlyphClass {
+    HorizontalLine,
+    VerticalLine,
+    Diagonal,
+    Cross,
+    Circle,
+    Square,
+    Digit(u8),
+}
+
+/// Seven-segment stroke endpoints: top, top-right, bottom-right, bottom, bottom-left, top-left, middle
+const SEGMENTS: [((f32, f32), (f32, f32)); 7] = [
+    ((0.25, 0.1), (0.75, 0.1)),
+    ((0.75, 0.1), (0.75, 0.5)),
+    ((0.75, 0.5), (0.75, 0.9)),
+    ((0.25, 0.9), (0.75, 0.9)),
+    ((0.25, 0.5), (0.25, 0.9)),
+    ((0.25, 0.1), (0.25, 0.5)),
+    ((0.25, 0.5), (0.75, 0.5)),
+];
+
+/// Segments lit for each digit, indexing into `SEGMENTS`
+const DIGIT_SEGMENTS: [&[usize]; 10] = [
+    &[0, 1, 2, 3, 4, 5],
+    &[1, 2],
+    &[0, 1, 6, 4, 3],
+    &[0, 1, 6, 2, 3],
+    &[5, 6, 1, 2],
+    &[0, 5, 6, 2, 3],
+    &[0, 5, 4, 3, 2, 6],
+    &[0, 1, 2],
+    &[0, 1, 2, 3, 4, 5, 6],
+    &[0, 1, 2, 3, 5, 6],
+];
+
+impl GlyphClass {
+    pub fn shapes() -> Vec<GlyphClass> {
+        vec![
+            GlyphClass::HorizontalLine,
+            GlyphClass::VerticalLine,
+            GlyphClass::Diagonal,
+            GlyphClass::Cross,
+            GlyphClass::Circle,
+            GlyphClass::Square,
+        ]
+    }
+
+    pub fn digits() -> Vec<GlyphClass> {
+        (0..10).map(GlyphClass::Digit).collect()
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            GlyphClass::Digit(digit) => format!("digit_{}", digit),
+            other => format!("{:?}", other).to_lowercase(),
+        }
+    }
+
+    /// Line strokes of the glyph in a unit square
+    pub fn strokes(&self) -> Vec<((f32, f32), (f32, f32))> {
+        match self {
+            GlyphClass::HorizontalLine => vec![((0.1, 0.5), (0.9, 0.5))],
+            GlyphClass::VerticalLine => vec![((0.5, 0.1), (0.5, 0.9))],
+            GlyphClass::Diagonal => vec![((0.1, 0.1), (0.9, 0.9))],
+            GlyphClass::Cross => vec![((0.1, 0.1), (0.9, 0.9)), ((0.9, 0.1), (0.1, 0.9))],
+            GlyphClass::Circle => {
+                let point = |i: usize| {
+                    let angle = 2.0 * PI * i as f32 / 16.0;
+                    (0.5 + 0.38 * angle.cos(), 0.5 + 0.38 * angle.sin())
+                };
+                (0..16).map(|i| (point(i), point(i + 1))).collect()
+            }
+            GlyphClass::Square => vec![
+                ((0.15, 0.15), (0.85, 0.15)),
+                ((0.85, 0.15), (0.85, 0.85)),
+                ((0.85, 0.85), (0.15, 0.85)),
+                ((0.15, 0.85), (0.15, 0.15)),
+            ],
+            GlyphClass::Digit(digit) => DIGIT_SEGMENTS[*digit as usize % 10]
+                .iter()
+                .map(|&segment| SEGMENTS[segment])
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GlyphConfig {
+    /// Images are size x size pixels, one network input per pixel
+    pub size: usize,
+    pub classes: Vec<GlyphClass>,
+    pub samples_per_class: usize,
+    /// Probability of flipping each pixel
+    pub noise: f32,
+    /// Largest shift in pixels, applied independently on each axis
+    pub max_shift: i32,
+    /// Glyphs are scaled by a random factor between this and 1.0
+    pub min_scale: f32,
+    pub seed: u64,
+}
+
+impl Default for GlyphConfig {
+    fn default() -> Self {
+        GlyphConfig {
+            size: 8,
+            classes: GlyphClass::shapes(),
+            samples_per_class: 50,
+            noise: 0.02,
+            max_shift: 1,
+            min_scale: 0.8,
+            seed: 0,
+        }
+    }
+}
+
+impl GlyphConfig {
+    /// Generates a labelled image dataset with one-hot targets, classes in `classes` order
+    pub fn generate(&self) -> Dataset {
+        assert!(
+            self.size * self.size + self.classes.len() <= 256,
+            "Pixels plus classes must fit in the 256 neuron seeds of a genome"
+        );
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut features = Vec::with_capacity(self.classes.len() * self.samples_per_class);
+        let mut targets = Vec::with_capacity(features.capacity());
+
+        for _ in 0..self.samples_per_class {
+            for (label, class) in self.classes.iter().enumerate() {
+                features.push(self.draw(class, &mut rng));
+                targets.push((0..self.classes.len()).map(|i| if i == label { 1.0 } else { 0.0 }).collect());
+            }
+        }
+
+        Dataset {
+            feature_names: (0..self.size * self.size)
+                .map(|pixel| format!("p{}_{}", pixel / self.size, pixel % self.size))
+                .collect(),
+            features,
+            targets,
+            class_names: self.classes.iter().map(|class| class.name()).collect(),
+        }
+    }
+
+    /// Rasterizes one randomly scaled, shifted and noisy glyph, row-major
+    pub fn draw<R: Rng>(&self, class: &GlyphClass, rng: &mut R) -> Vec<f32> {
+        let size = self.size as f32;
+        let scale = rng.gen_range(self.min_scale.min(1.0)..=1.0);
+        let shift = (
+            rng.gen_range(-self.max_shift..=self.max_shift) as f32,
+            rng.gen_range(-self.max_shift..=self.max_shift) as f32,
+        );
+        let to_pixel = |(x, y): (f32, f32)| {
+            (
+                (0.5 + (x - 0.5) * scale) * size + shift.0,
+                (0.5 + (y - 0.5) * scale) * size + shift.1,
+            )
+        };
+
+        let mut pixels = vec![0.0; self.size * self.size];
+        for (from, to) in class.strokes() {
+            let (from, to) = (to_pixel(from), to_pixel(to));
+            let samples = ((to.0 - from.0).abs().max((to.1 - from.1).abs()) * 2.0).ceil() as usize + 1;
+            for i in 0..=samples {
+                let t = i as f32 / samples as f32;
+                let x = (from.0 + (to.0 - from.0) * t).floor();
+                let y = (from.1 + (to.1 - from.1) * t).floor();
+                if x >= 0.0 && y >= 0.0 && x < size && y < size {
+                    pixels[y as usize * self.size + x as usize] = 1.0;
+                }
+            }
+        }
+        for pixel in pixels.iter_mut() {
+            if rng.gen::<f32>() < self.noise {
+                *pixel = 1.0 - *pixel;
+            }
+        }
+        pixels
+    }
+}
+
+/// Draws a row-major image as text, '#' for ink and '.' for background
+pub fn render_glyph(pixels: &[f32], size: usize) -> String {
+    pixels
+        .chunks(size)
+        .map(|row| row.iter().map(|&pixel| if pixel > 0.5 { '#' } else { '.' }).collect::<String>())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
@@ -0,0 +1,438 @@
+use crate::{Genome, Network};
+use rand::prelude::*;
+use rand::rngs::StdRng;
+
+/// Grid directions in (x, y) with y growing downwards: up, right, down, left
+const DIRECTIONS: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Index of the largest output, ties going to the lowest index
+fn argmax(outputs: &[f32]) -> usize {
+    outputs
+        .iter()
+        .enumerate()
+        .fold(0, |best, (i, &value)| if value > outputs[best] { i } else { best })
+}
+
+// SNAKE
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Turn {
+    Left,
+    Straight,
+    Right,
+}
+
+#[derive(Debug, Clone)]
+pub struct SnakeConfig {
+    pub width: i32,
+    pub height: i32,
+    /// The snake sees the (2 * view_radius + 1)^2 - 1 cells around its head, rotated to its heading
+    pub view_radius: i32,
+    pub max_steps: usize,
+    /// Episode ends if no food is eaten for this many steps
+    pub starvation_steps: usize,
+    pub seed: u64,
+}
+
+impl Default for SnakeConfig {
+    fn default() -> Self {
+        SnakeConfig {
+            width: 12,
+            height: 12,
+            view_radius: 1,
+            max_steps: 500,
+            starvation_steps: 150,
+            seed: 0,
+        }
+    }
+}
+
+impl SnakeConfig {
+    /// Network inputs: the local cells followed by food direction (forward, right)
+    pub fn input_count(&self) -> u16 {
+        let side = 2 * self.view_radius + 1;
+        (side * side - 1 + 2) as u16
+    }
+
+    /// Network outputs: left, straight and right, the largest one is taken
+    pub fn output_count(&self) -> u16 {
+        3
+    }
+
+    /// Creates an empty network with the inputs and outputs the snake expects
+    pub fn new_network(&self) -> Network {
+        Network::from_genome(Genome::new(self.input_count(), self.output_count()))
+    }
+}
+
+/// Board state of a snake game, one per frame of a replay
+#[derive(Debug, Clone)]
+pub struct SnakeState {
+    pub width: i32,
+    pub height: i32,
+    /// Body cells, head first
+    pub body: Vec<(i32, i32)>,
+    /// Index into up, right, down, left
+    pub heading: usize,
+    pub food: (i32, i32),
+}
+
+impl SnakeState {
+    /// Draws the board: '#' border, '@' head, 'o' body, '*' food
+    pub fn render(&self) -> String {
+        let mut text = "#".repeat(self.width as usize + 2) + "\n";
+        for y in 0..self.height {
+            text.push('#');
+            for x in 0..self.width {
+                let cell = if self.body.first() == Some(&(x, y)) {
+                    '@'
+                } else if self.body.contains(&(x, y)) {
+                    'o'
+                } else if self.food == (x, y) {
+                    '*'
+                } else {
+                    ' '
+                };
+                text.push(cell);
+            }
+            text.push_str("#\n");
+        }
+        text + &"#".repeat(self.width as usize + 2)
+    }
+
+    fn blocked(&self, cell: (i32, i32)) -> bool {
+        cell.0 < 0 || cell.1 < 0 || cell.0 >= self.width || cell.1 >= self.height || self.body.contains(&cell)
+    }
+}
+
+/// Result of a snake episode. The actions together with the config are enough to replay it.
+#[derive(Debug, Clone)]
+pub struct SnakeOutcome {
+    pub food_eaten: usize,
+    pub steps: usize,
+    pub died: bool,
+    /// Food eaten plus a small bonus for survival time
+    pub score: f32,
+    pub actions: Vec<Turn>,
+}
+
+/// A deterministic snake game, food placement is driven by the config seed
+pub struct SnakeGame {
+    pub config: SnakeConfig,
+    pub state: SnakeState,
+    pub food_eaten: usize,
+    pub steps: usize,
+    pub alive: bool,
+    steps_since_food: usize,
+    rng: StdRng,
+}
+
+impl SnakeGame {
+    pub fn new(config: SnakeConfig) -> Self {
+        assert!(config.width >= 4 && config.height >= 4, "Snake board must be at least 4x4");
+        let center = (config.width / 2, config.height / 2);
+        let mut game = SnakeGame {
+            state: SnakeState {
+                width: config.width,
+                height: config.height,
+                body: vec![center, (center.0 - 1, center.1), (center.0 - 2, center.1)],
+                heading: 1,
+                food: (0, 0),
+            },
+            rng: StdRng::seed_from_u64(config.seed),
+            config,
+            food_eaten: 0,
+            steps: 0,
+            alive: true,
+            steps_since_food: 0,
+        };
+        game.place_food();
+        game
+    }
+
+    /// Local cells (1.0 if blocked) around the head rotated to the heading, then food offset along forward and right
+    pub fn sensors(&self) -> Vec<f32> {
+        let head = self.state.body[0];
+        let forward = DIRECTIONS[self.state.heading];
+        let right = DIRECTIONS[(self.state.heading + 1) % 4];
+        let radius = self.config.view_radius;
+
+        let mut inputs = Vec::with_capacity(self.config.input_count() as usize);
+        for ahead in (-radius..=radius).rev() {
+            for side in -radius..=radius {
+                if ahead == 0 && side == 0 {
+                    continue;
+                }
+                let cell = (
+                    head.0 + ahead * forward.0 + side * right.0,
+                    head.1 + ahead * forward.1 + side * right.1,
+                );
+                inputs.push(if self.state.blocked(cell) { 1.0 } else { 0.0 });
+            }
+        }
+
+        let offset = (self.state.food.0 - head.0, self.state.food.1 - head.1);
+        let size = self.config.width.max(self.config.height) as f32;
+        inputs.push((offset.0 * forward.0 + offset.1 * forward.1) as f32 / size);
+        inputs.push((offset.0 * right.0 + offset.1 * right.1) as f32 / size);
+        inputs
+    }
+
+    /// Advances one step. Returns whether the episode is still running.
+    pub fn step(&mut self, turn: Turn) -> bool {
+        if !self.is_running() {
+            return false;
+        }
+        self.state.heading = match turn {
+            Turn::Left => (self.state.heading + 3) % 4,
+            Turn::Straight => self.state.heading,
+            Turn::Right => (self.state.heading + 1) % 4,
+        };
+        let direction = DIRECTIONS[self.state.heading];
+        let head = self.state.body[0];
+        let next = (head.0 + direction.0, head.1 + direction.1);
+        self.steps += 1;
+        self.steps_since_food += 1;
+
+        // The tail moves away this step unless the snake grows, so it does not block the head
+        let eating = next == self.state.food;
+        let tail = if eating { None } else { self.state.body.pop() };
+        if self.state.blocked(next) {
+            self.state.body.extend(tail);
+            self.alive = false;
+            return false;
+        }
+        self.state.body.insert(0, next);
+        if eating {
+            self.food_eaten += 1;
+            self.steps_since_food = 0;
+            self.place_food();
+        }
+        self.is_running()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.alive
+            && self.steps < self.config.max_steps
+            && self.steps_since_food < self.config.starvation_steps
+            && self.state.body.len() < (self.config.width * self.config.height) as usize
+    }
+
+    /// Plays a full episode with the network choosing the turn each step
+    pub fn play(config: &SnakeConfig, network: &mut Network) -> SnakeOutcome {
+        assert_eq!(network.input_count(), config.input_count() as usize, "Snake needs {} inputs", config.input_count());
+        assert_eq!(network.output_count(), config.output_count() as usize, "Snake needs {} outputs", config.output_count());
+        network.reset();
+
+        let mut game = SnakeGame::new(config.clone());
+        let mut actions = Vec::new();
+        while game.is_running() {
+            let turn = [Turn::Left, Turn::Straight, Turn::Right][argmax(&network.activate(&game.sensors()))];
+            actions.push(turn);
+            game.step(turn);
+        }
+
+        SnakeOutcome {
+            food_eaten: game.food_eaten,
+            steps: game.steps,
+            died: !game.alive,
+            score: game.food_eaten as f32 + game.steps as f32 / config.max_steps as f32 * 0.1,
+            actions,
+        }
+    }
+
+    /// Re-runs recorded actions and returns the board before the first step and after every step
+    pub fn replay(config: &SnakeConfig, actions: &[Turn]) -> Vec<SnakeState> {
+        let mut game = SnakeGame::new(config.clone());
+        let mut frames = vec![game.state.clone()];
+        for &turn in actions {
+            game.step(turn);
+            frames.push(game.state.clone());
+        }
+        frames
+    }
+
+    fn place_food(&mut self) {
+        let free: Vec<(i32, i32)> = (0..self.config.height)
+            .flat_map(|y| (0..self.config.width).map(move |x| (x, y)))
+            .filter(|cell| !self.state.body.contains(cell))
+            .collect();
+        if let Some(&cell) = free.choose(&mut self.rng) {
+            self.state.food = cell;
+        }
+    }
+}
+
+// PREDATOR-PREY
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Predator,
+    Prey,
+}
+
+#[derive(Debug, Clone)]
+pub struct PredatorPreyConfig {
+    pub width: i32,
+    pub height: i32,
+    pub predators: usize,
+    pub prey: usize,
+    pub max_steps: usize,
+    pub seed: u64,
+}
+
+impl Default for PredatorPreyConfig {
+    fn default() -> Self {
+        PredatorPreyConfig {
+            width: 15,
+            height: 15,
+            predators: 3,
+            prey: 2,
+            max_steps: 100,
+            seed: 0,
+        }
+    }
+}
+
+impl PredatorPreyConfig {
+    /// Network inputs: offset to the nearest opponent and to the nearest teammate, scaled by board size
+    pub fn input_count(&self) -> u16 {
+        4
+    }
+
+    /// Network outputs: stay, up, right, down, left, the largest one is taken
+    pub fn output_count(&self) -> u16 {
+        5
+    }
+
+    /// Creates an empty network with the inputs and outputs every agent expects
+    pub fn new_network(&self) -> Network {
+        Network::from_genome(Genome::new(self.input_count(), self.output_count()))
+    }
+
+    /// Roles in agent order: all predators, then all prey
+    pub fn roles(&self) -> Vec<Role> {
+        let mut roles = vec![Role::Predator; self.predators];
+        roles.extend(vec![Role::Prey; self.prey]);
+        roles
+    }
+}
+
+/// Result of a predator-prey episode
+#[derive(Debug, Clone)]
+pub struct PredatorPreyOutcome {
+    /// Score per agent: captures for predators, fraction of the episode survived for prey
+    pub scores: Vec<f32>,
+    pub steps: usize,
+    /// Agent positions before the first step and after every step, None once a prey is caught
+    pub frames: Vec<Vec<Option<(i32, i32)>>>,
+}
+
+impl PredatorPreyOutcome {
+    /// Draws one frame of the replay: 'P' predator, 'r' prey
+    pub fn render_frame(&self, config: &PredatorPreyConfig, frame: usize) -> String {
+        let roles = config.roles();
+        let mut text = String::new();
+        for y in 0..config.height {
+            for x in 0..config.width {
+                let agent = self.frames[frame].iter().position(|&position| position == Some((x, y)));
+                text.push(match agent.map(|i| roles[i]) {
+                    Some(Role::Predator) => 'P',
+                    Some(Role::Prey) => 'r',
+                    None => '.',
+                });
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+/// Runs a deterministic predator-prey episode, each agent controlled by its own network.
+/// `networks` follows `config.roles()`. Each step all prey move, then all predators. A predator
+/// landing on a prey catches it, and so does a prey landing on a predator.
+pub fn play_predator_prey(config: &PredatorPreyConfig, networks: &mut [Network]) -> PredatorPreyOutcome {
+    let roles = config.roles();
+    assert_eq!(networks.len(), roles.len(), "Expected one network per agent");
+    for network in networks.iter_mut() {
+        assert_eq!(network.input_count(), config.input_count() as usize, "Agents need {} inputs", config.input_count());
+        assert_eq!(network.output_count(), config.output_count() as usize, "Agents need {} outputs", config.output_count());
+        network.reset();
+    }
+
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut cells: Vec<(i32, i32)> = (0..config.height)
+        .flat_map(|y| (0..config.width).map(move |x| (x, y)))
+        .collect();
+    assert!(cells.len() >= roles.len(), "Board is too small for all agents");
+    cells.shuffle(&mut rng);
+    let mut positions: Vec<Option<(i32, i32)>> = cells[..roles.len()].iter().copied().map(Some).collect();
+
+    let mut scores = vec![0.0; roles.len()];
+    let mut frames = vec![positions.clone()];
+    let mut steps = 0;
+    let prey_left = |positions: &[Option<(i32, i32)>]| (config.predators..roles.len()).any(|i| positions[i].is_some());
+
+    while steps < config.max_steps && prey_left(&positions) {
+        steps += 1;
+        let order = (config.predators..roles.len()).chain(0..config.predators);
+        for agent in order {
+            let Some(position) = positions[agent] else { continue };
+            let inputs = agent_sensors(config, &roles, &positions, agent);
+            let action = argmax(&networks[agent].activate(&inputs));
+            if action > 0 {
+                let direction = DIRECTIONS[action - 1];
+                positions[agent] = Some((
+                    (position.0 + direction.0).clamp(0, config.width - 1),
+                    (position.1 + direction.1).clamp(0, config.height - 1),
+                ));
+            }
+            // A catch happens whichever side moves onto the other's cell
+            let catches: Vec<(usize, usize)> = match roles[agent] {
+                Role::Predator => (config.predators..roles.len())
+                    .filter(|&prey| positions[prey] == positions[agent])
+                    .map(|prey| (agent, prey))
+                    .collect(),
+                Role::Prey => (0..config.predators)
+                    .find(|&predator| positions[predator] == positions[agent])
+                    .map(|predator| (predator, agent))
+                    .into_iter()
+                    .collect(),
+            };
+            for (predator, prey) in catches {
+                positions[prey] = None;
+                scores[predator] += 1.0;
+            }
+        }
+        for prey in config.predators..roles.len() {
+            if positions[prey].is_some() {
+                scores[prey] += 1.0 / config.max_steps as f32;
+            }
+        }
+        frames.push(positions.clone());
+    }
+
+    PredatorPreyOutcome { scores, steps, frames }
+}
+
+/// Offsets to the nearest opponent and nearest teammate, zero when there is none
+fn agent_sensors(config: &PredatorPreyConfig, roles: &[Role], positions: &[Option<(i32, i32)>], agent: usize) -> Vec<f32> {
+    let own = positions[agent].expect("Only living agents sense");
+    let nearest = |teammates: bool| {
+        positions
+            .iter()
+            .enumerate()
+            .filter(|&(i, position)| i != agent && position.is_some() && (roles[i] == roles[agent]) == teammates)
+            .map(|(_, position)| position.unwrap())
+            .min_by_key(|other| (other.0 - own.0).abs() + (other.1 - own.1).abs())
+            .map_or([0.0, 0.0], |other| {
+                [
+                    (other.0 - own.0) as f32 / config.width as f32,
+                    (other.1 - own.1) as f32 / config.height as f32,
+                ]
+            })
+    };
+    let opponent = nearest(false);
+    let teammate = nearest(true);
+    vec![opponent[0], opponent[1], teammate[0], teammate[1]]
+}
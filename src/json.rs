@@ -0,0 +1,963 @@
+//! JSON export and import for genomes, networks and run summaries.
+//!
+//! Every document carries a `format` name and a `version`. Ids are `[seed, position]` arrays and
+//! synapses name their destination directly, so readers never need to decode the f32-packed
+//! target in `Gene::local_data`. Non-finite numbers are written as `null`.
+//!
+//! `aster_genome` v1, genes in genome order:
+//!
+//! ```text
+//! {
+//!   "format": "aster_genome", "version": 1,
+//!   "config": {"connection_bias": 4, "inclusion_probability": 0.5},
+//!   "genes": [
+//!     {"kind": "input", "id": [0, 0], "children": 1, "bias": 0, "activation": 0},
+//!     {"kind": "synapse", "from": [0, 0], "to": [0, 1], "weight": 0.5, "enabled": true},
+//!     ...
+//!   ],
+//!   "lineage": {"id": 42, "parents": [17], "birth_generation": 3, "mutations": ["add_neuron"]}
+//! }
+//! ```
+//!
+//! `kind` is one of `input`, `hidden`, `output` or `synapse`. `lineage` is optional on import,
+//! a genome without one gets a fresh id like `Lineage::root`. Imported ids are reserved, so genomes
+//! created afterwards never reuse them.
+//!
+//! `aster_network` v1, the evaluated state of a `Network`:
+//!
+//! ```text
+//! {
+//!   "format": "aster_network", "version": 1, "input_count": 2, "output_count": 1,
+//!   "neurons": [{"id": [0, 0], "kind": "input", "bias": 0, "activation": 0.5}, ...],
+//!   "synapses": [{"from": [0, 0], "to": [0, 1], "weight": 0.5, "enabled": true}, ...],
+//!   "genome": {aster_genome document}
+//! }
+//! ```
+//!
+//! Neurons are listed in evaluation order and synapses by source then destination. Importing
+//! rebuilds the network from `genome` and restores each neuron's `activation`.
+//!
+//! `aster_run` v1, a list of `GenerationSummary`:
+//!
+//! ```text
+//! {
+//!   "format": "aster_run", "version": 1,
+//!   "generations": [{
+//!     "generation": 0, "population_size": 150,
+//!     "best_fitness": 3.9, "mean_fitness": 2.1, "median_fitness": 2.0, "worst_fitness": 0.4,
+//!     "fitness_std_dev": 0.7, "mean_hidden_neurons": 3.2, "mean_synapses": 6.5,
+//!     "species_sizes": [90, 60],
+//!     "champion": {aster_genome document}
+//!   }, ...]
+//! }
+//! ```
+//!
+//! `aster_genealogy` v1, a `Genealogy` log in id order:
+//!
+//! ```text
+//! {
+//!   "format": "aster_genealogy", "version": 1,
+//!   "genomes": [
+//!     {"id": 42, "parents": [17], "birth_generation": 3, "last_generation": 5,
+//!      "mutations": ["add_neuron", "add_synapse"], "fitness": 2.5},
+//!     ...
+//!   ]
+//! }
+//! ```
+//!
+//! `fitness` is `null` for genomes that never scored a finite fitness.
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{
+    convert_f32_to_id, convert_id_to_f32, Gene, GeneType, Genealogy, GenealogyEntry, GenerationSummary, Genome,
+    GenomeError, Lineage, MutationConfig, MutationOperator, Network, SynapseStatus,
+};
+
+/// Version written for all four document formats
+pub const JSON_FORMAT_VERSION: u64 = 1;
+
+/// Parsed JSON document. Numbers keep their source text so f32 values round-trip exactly.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<JsonValue>),
+    /// Members in document order
+    Object(Vec<(String, JsonValue)>),
+}
+
+#[derive(Debug)]
+pub enum JsonError {
+    Syntax { line: usize, column: usize, message: String },
+    MissingField(String),
+    WrongType { field: String, expected: &'static str },
+    UnsupportedFormat { format: String, version: u64 },
+    Invalid(String),
+    Genome(GenomeError),
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JsonError::Syntax { line, column, message } => write!(f, "line {}, column {}: {}", line, column, message),
+            JsonError::MissingField(field) => write!(f, "missing field {:?}", field),
+            JsonError::WrongType { field, expected } => write!(f, "field {:?} should be {}", field, expected),
+            JsonError::UnsupportedFormat { format, version } => {
+                write!(f, "unsupported document format {:?} version {}", format, version)
+            }
+            JsonError::Invalid(message) => write!(f, "{}", message),
+            JsonError::Genome(err) => write!(f, "invalid genome: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+impl From<f32> for JsonValue {
+    fn from(value: f32) -> Self {
+        if value.is_finite() {
+            JsonValue::Number(value.to_string())
+        } else {
+            JsonValue::Null
+        }
+    }
+}
+
+impl From<f64> for JsonValue {
+    fn from(value: f64) -> Self {
+        if value.is_finite() {
+            JsonValue::Number(value.to_string())
+        } else {
+            JsonValue::Null
+        }
+    }
+}
+
+impl From<usize> for JsonValue {
+    fn from(value: usize) -> Self {
+        JsonValue::Number(value.to_string())
+    }
+}
+
+impl From<u64> for JsonValue {
+    fn from(value: u64) -> Self {
+        JsonValue::Number(value.to_string())
+    }
+}
+
+impl From<bool> for JsonValue {
+    fn from(value: bool) -> Self {
+        JsonValue::Bool(value)
+    }
+}
+
+impl From<&str> for JsonValue {
+    fn from(value: &str) -> Self {
+        JsonValue::String(value.to_string())
+    }
+}
+
+impl From<String> for JsonValue {
+    fn from(value: String) -> Self {
+        JsonValue::String(value)
+    }
+}
+
+impl<T: Into<JsonValue>> From<Vec<T>> for JsonValue {
+    fn from(values: Vec<T>) -> Self {
+        JsonValue::Array(values.into_iter().map(Into::into).collect())
+    }
+}
+
+impl From<[u8; 2]> for JsonValue {
+    fn from(id: [u8; 2]) -> Self {
+        JsonValue::Array(vec![(id[0] as usize).into(), (id[1] as usize).into()])
+    }
+}
+
+impl JsonValue {
+    /// Builds an object from `(key, value)` pairs
+    pub fn object<const N: usize>(members: [(&str, JsonValue); N]) -> Self {
+        JsonValue::Object(members.into_iter().map(|(key, value)| (key.to_string(), value)).collect())
+    }
+
+    /// Member of an object, None for missing keys and non-objects
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(members) => members.iter().find(|(name, _)| name == key).map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Indented rendering. Objects and arrays that only hold scalars stay on one line,
+    /// so each gene or neuron is one line and documents diff cleanly.
+    pub fn pretty(&self) -> String {
+        let mut text = String::new();
+        self.write_pretty(&mut text, 0);
+        text
+    }
+
+    fn is_flat(&self) -> bool {
+        let scalar = |value: &JsonValue| match value {
+            JsonValue::Array(items) => items.iter().all(|item| !matches!(item, JsonValue::Array(_) | JsonValue::Object(_))),
+            JsonValue::Object(_) => false,
+            _ => true,
+        };
+        match self {
+            JsonValue::Array(items) => items.iter().all(scalar),
+            JsonValue::Object(members) => members.iter().all(|(_, value)| scalar(value)),
+            _ => true,
+        }
+    }
+
+    fn write_pretty(&self, text: &mut String, depth: usize) {
+        if self.is_flat() {
+            *text += &self.to_string();
+            return;
+        }
+        let indent = "  ".repeat(depth + 1);
+        let (open, close, count) = match self {
+            JsonValue::Array(items) => ('[', ']', items.len()),
+            JsonValue::Object(members) => ('{', '}', members.len()),
+            _ => unreachable!(),
+        };
+        text.push(open);
+        for index in 0..count {
+            text.push('\n');
+            *text += &indent;
+            match self {
+                JsonValue::Array(items) => items[index].write_pretty(text, depth + 1),
+                JsonValue::Object(members) => {
+                    write_string(text, &members[index].0);
+                    *text += ": ";
+                    members[index].1.write_pretty(text, depth + 1);
+                }
+                _ => unreachable!(),
+            }
+            if index + 1 < count {
+                text.push(',');
+            }
+        }
+        text.push('\n');
+        *text += &"  ".repeat(depth);
+        text.push(close);
+    }
+}
+
+impl fmt::Display for JsonValue {
+    /// Compact rendering on a single line
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JsonValue::Null => write!(f, "null"),
+            JsonValue::Bool(value) => write!(f, "{}", value),
+            JsonValue::Number(value) => write!(f, "{}", value),
+            JsonValue::String(value) => {
+                let mut text = String::new();
+                write_string(&mut text, value);
+                write!(f, "{}", text)
+            }
+            JsonValue::Array(items) => {
+                write!(f, "[")?;
+                for (index, item) in items.iter().enumerate() {
+                    write!(f, "{}{}", if index > 0 { ", " } else { "" }, item)?;
+                }
+                write!(f, "]")
+            }
+            JsonValue::Object(members) => {
+                write!(f, "{{")?;
+                for (index, (key, value)) in members.iter().enumerate() {
+                    let mut name = String::new();
+                    write_string(&mut name, key);
+                    write!(f, "{}{}: {}", if index > 0 { ", " } else { "" }, name, value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn write_string(text: &mut String, value: &str) {
+    text.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => *text += "\\\"",
+            '\\' => *text += "\\\\",
+            '\n' => *text += "\\n",
+            '\r' => *text += "\\r",
+            '\t' => *text += "\\t",
+            c if (c as u32) < 0x20 => *text += &format!("\\u{:04x}", c as u32),
+            c => text.push(c),
+        }
+    }
+    text.push('"');
+}
+
+impl FromStr for JsonValue {
+    type Err = JsonError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { chars: text.chars().collect(), position: 0, depth: 0 };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.position < parser.chars.len() {
+            return Err(parser.error("unexpected text after the document"));
+        }
+        Ok(value)
+    }
+}
+
+/// Deepest array and object nesting `JsonValue::from_str` accepts, deeper documents are rejected
+/// before they can overflow the stack
+pub const JSON_MAX_DEPTH: usize = 128;
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn error(&self, message: &str) -> JsonError {
+        let before = &self.chars[..self.position.min(self.chars.len())];
+        let line = before.iter().filter(|&&c| c == '\n').count() + 1;
+        let column = before.iter().rev().take_while(|&&c| c != '\n').count() + 1;
+        JsonError::Syntax { line, column, message: message.to_string() }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.get(self.position).is_some_and(|c| c.is_whitespace()) {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), JsonError> {
+        self.skip_whitespace();
+        if self.chars.get(self.position) != Some(&expected) {
+            return Err(self.error(&format!("expected {:?}", expected)));
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    fn keyword(&mut self, word: &str, value: JsonValue) -> Result<JsonValue, JsonError> {
+        let end = self.position + word.chars().count();
+        if end > self.chars.len() || !self.chars[self.position..end].iter().copied().eq(word.chars()) {
+            return Err(self.error("expected a value"));
+        }
+        self.position = end;
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<JsonValue, JsonError> {
+        self.skip_whitespace();
+        match self.chars.get(self.position) {
+            None => Err(self.error("unexpected end of document")),
+            Some('n') => self.keyword("null", JsonValue::Null),
+            Some('t') => self.keyword("true", JsonValue::Bool(true)),
+            Some('f') => self.keyword("false", JsonValue::Bool(false)),
+            Some('"') => Ok(JsonValue::String(self.string()?)),
+            Some('[') => self.nested(Self::array),
+            Some('{') => self.nested(Self::object),
+            Some(c) if *c == '-' || c.is_ascii_digit() => self.number(),
+            Some(_) => Err(self.error("expected a value")),
+        }
+    }
+
+/// Scans a number with the JSON grammar: an optional `-`, `0` or digits not starting with `0`,
+    /// then an optional fraction and an optional exponent, each with at least one digit
+    fn number(&mut self) -> Result<JsonValue, JsonError> {
+        let start = self.position;
+        self.skip('-');
+        if self.skip('0') {
+            if self.chars.get(self.position).is_some_and(|c| c.is_ascii_digit()) {
+                return Err(self.error("leading zeros are not allowed"));
+            }
+        } else {
+            self.digits()?;
+        }
+        if self.skip('.') {
+            self.digits()?;
+        }
+        if self.skip('e') || self.skip('E') {
+            let _ = self.skip('+') || self.skip('-');
+            self.digits()?;
+        }
+        Ok(JsonValue::Number(self.chars[start..self.position].iter().collect()))
+    }
+
+    /// Consumes `expected` if it is the next character
+    fn skip(&mut self, expected: char) -> bool {
+        let found = self.chars.get(self.position) == Some(&expected);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    /// Consumes one or more digits
+    fn digits(&mut self) -> Result<(), JsonError> {
+        let start = self.position;
+        while self.chars.get(self.position).is_some_and(|c| c.is_ascii_digit()) {
+            self.position += 1;
+        }
+        if self.position == start {
+            return Err(self.error("expected a digit"));
+        }
+        Ok(())
+    }
+
+    /// Parses an array or object one level deeper, refusing documents nested past `JSON_MAX_DEPTH`
+    fn nested(&mut self, parse: fn(&mut Self) -> Result<JsonValue, JsonError>) -> Result<JsonValue, JsonError> {
+        if self.depth == JSON_MAX_DEPTH {
+            return Err(self.error(&format!("nesting deeper than {} levels", JSON_MAX_DEPTH)));
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn array(&mut self) -> Result<JsonValue, JsonError> {
+        self.position += 1;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.chars.get(self.position) == Some(&']') {
+            self.position += 1;
+            return Ok(JsonValue::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.chars.get(self.position) {
+                Some(',') => self.position += 1,
+                Some(']') => {
+                    self.position += 1;
+                    return Ok(JsonValue::Array(items));
+                }
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<JsonValue, JsonError> {
+        self.position += 1;
+        let mut members = Vec::new();
+        self.skip_whitespace();
+        if self.chars.get(self.position) == Some(&'}') {
+            self.position += 1;
+            return Ok(JsonValue::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.chars.get(self.position) != Some(&'"') {
+                return Err(self.error("expected a member name"));
+            }
+            let key = self.string()?;
+            self.expect(':')?;
+            members.push((key, self.value()?));
+            self.skip_whitespace();
+            match self.chars.get(self.position) {
+                Some(',') => self.position += 1,
+                Some('}') => {
+                    self.position += 1;
+                    return Ok(JsonValue::Object(members));
+                }
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, JsonError> {
+        self.position += 1;
+        let mut value = String::new();
+        loop {
+            let Some(&c) = self.chars.get(self.position) else {
+                return Err(self.error("unterminated string"));
+            };
+            self.position += 1;
+            match c {
+                '"' => return Ok(value),
+                '\\' => {
+                    let escaped = match self.chars.get(self.position) {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => {
+                            self.position += 1;
+                            let mut code = self.hex4()?;
+                            if (0xD800..0xDC00).contains(&code) && self.chars.get(self.position..self.position + 2) == Some(&['\\', 'u']) {
+                                self.position += 2;
+                                let low = self.hex4()?;
+                                code = 0x10000 + ((code - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF);
+                            }
+                            value.push(char::from_u32(code).unwrap_or('\u{FFFD}'));
+                            continue;
+                        }
+                        _ => return Err(self.error("invalid escape")),
+                    };
+                    self.position += 1;
+                    value.push(escaped);
+                }
+                c => value.push(c),
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Result<u32, JsonError> {
+        let digits: String = self.chars.iter().skip(self.position).take(4).collect();
+        let code = u32::from_str_radix(&digits, 16)
+            .ok()
+            .filter(|_| digits.len() == 4)
+            .ok_or_else(|| self.error("expected four hex digits"))?;
+        self.position += 4;
+        Ok(code)
+    }
+}
+
+/// Typed access to the members of one JSON object, errors name the field
+struct Fields<'a> {
+    value: &'a JsonValue,
+}
+
+impl<'a> Fields<'a> {
+    fn of(value: &'a JsonValue, expected_format: &str) -> Result<Self, JsonError> {
+        let fields = Fields { value };
+        let format = fields.str("format")?;
+        let version = fields.u64("version")?;
+        if format != expected_format || version != JSON_FORMAT_VERSION {
+            return Err(JsonError::UnsupportedFormat { format: format.to_string(), version });
+        }
+        Ok(fields)
+    }
+
+    fn get(&self, field: &str) -> Result<&'a JsonValue, JsonError> {
+        if !matches!(self.value, JsonValue::Object(_)) {
+            return Err(JsonError::WrongType { field: field.to_string(), expected: "a member of an object" });
+        }
+        self.value.get(field).ok_or_else(|| JsonError::MissingField(field.to_string()))
+    }
+
+    fn wrong(field: &str, expected: &'static str) -> JsonError {
+        JsonError::WrongType { field: field.to_string(), expected }
+    }
+
+    fn str(&self, field: &str) -> Result<&'a str, JsonError> {
+        match self.get(field)? {
+            JsonValue::String(value) => Ok(value),
+            _ => Err(Self::wrong(field, "a string")),
+        }
+    }
+
+    fn bool(&self, field: &str) -> Result<bool, JsonError> {
+        match self.get(field)? {
+            JsonValue::Bool(value) => Ok(*value),
+            _ => Err(Self::wrong(field, "true or false")),
+        }
+    }
+
+    fn u64(&self, field: &str) -> Result<u64, JsonError> {
+        match self.get(field)? {
+            JsonValue::Number(value) => value.parse().map_err(|_| Self::wrong(field, "a whole number")),
+            _ => Err(Self::wrong(field, "a whole number")),
+        }
+    }
+
+    /// Reads `null` as NaN, the inverse of how non-finite values are written
+    fn f32(&self, field: &str) -> Result<f32, JsonError> {
+        match self.get(field)? {
+            JsonValue::Number(value) => value.parse().map_err(|_| Self::wrong(field, "a number")),
+            JsonValue::Null => Ok(f32::NAN),
+            _ => Err(Self::wrong(field, "a number")),
+        }
+    }
+
+    fn id(&self, field: &str) -> Result<[u8; 2], JsonError> {
+        let parts = match self.get(field)? {
+            JsonValue::Array(parts) if parts.len() == 2 => parts,
+            _ => return Err(Self::wrong(field, "an id like [0, 1]")),
+        };
+        let part = |value: &JsonValue| match value {
+            JsonValue::Number(number) => number.parse::<u8>().ok(),
+            _ => None,
+        };
+        part(&parts[0])
+            .zip(part(&parts[1]))
+            .map(|(seed, position)| [seed, position])
+            .ok_or_else(|| Self::wrong(field, "an id like [0, 1]"))
+    }
+
+    fn array(&self, field: &str) -> Result<&'a [JsonValue], JsonError> {
+        match self.get(field)? {
+            JsonValue::Array(items) => Ok(items),
+            _ => Err(Self::wrong(field, "an array")),
+        }
+    }
+
+    /// Array of genome ids
+    fn ids(&self, field: &str) -> Result<Vec<u64>, JsonError> {
+        self.array(field)?
+            .iter()
+            .map(|id| match id {
+                JsonValue::Number(number) => number.parse().ok(),
+                _ => None,
+            })
+            .collect::<Option<Vec<u64>>>()
+            .ok_or_else(|| Self::wrong(field, "an array of genome ids"))
+    }
+
+    /// Array of `MutationOperator` names
+    fn mutations(&self, field: &str) -> Result<Vec<MutationOperator>, JsonError> {
+        self.array(field)?
+            .iter()
+            .map(|name| match name {
+                JsonValue::String(name) => name.parse().map_err(JsonError::Invalid),
+                _ => Err(Self::wrong(field, "an array of mutation names")),
+            })
+            .collect()
+    }
+}
+
+fn mutation_names(mutations: &[MutationOperator]) -> JsonValue {
+    JsonValue::Array(mutations.iter().map(|mutation| mutation.name().into()).collect())
+}
+
+fn gene_kind(gene_type: GeneType) -> &'static str {
+    match gene_type {
+        GeneType::Input => "input",
+        GeneType::Hidden => "hidden",
+        GeneType::Output => "output",
+        GeneType::Synapse => "synapse",
+    }
+}
+
+/// Whether a synapse flag means enabled, reporting an unknown status against gene `index` of the genome
+fn synapse_enabled(index: usize, flag: u8) -> Result<bool, GenomeError> {
+    match SynapseStatus::from_u8(flag) {
+        Some(status) => Ok(status == SynapseStatus::Enabled),
+        None => Err(GenomeError::UnknownSynapseStatus { gene: index, flag }),
+    }
+}
+
+impl Genome {
+    /// `aster_genome` document of the genome. Genes with an unknown type or synapse status have no
+    /// JSON form and are reported the way `validate` reports them.
+    pub fn to_json_value(&self) -> Result<JsonValue, GenomeError> {
+        let genes = self
+            .genes
+            .iter()
+            .enumerate()
+            .map(|(index, gene)| match GeneType::from_u8(gene.flag[0]) {
+                Some(GeneType::Synapse) => Ok(JsonValue::object([
+                    ("kind", "synapse".into()),
+                    ("from", gene.id.into()),
+                    ("to", convert_f32_to_id(gene.local_data).into()),
+                    ("weight", gene.extern_data.into()),
+                    ("enabled", synapse_enabled(index, gene.flag[1])?.into()),
+                ])),
+                Some(gene_type) => Ok(JsonValue::object([
+                    ("kind", gene_kind(gene_type).into()),
+                    ("id", gene.id.into()),
+                    ("children", (gene.flag[1] as usize).into()),
+                    ("bias", gene.local_data.into()),
+                    ("activation", gene.extern_data.into()),
+                ])),
+                None => Err(GenomeError::UnknownGeneType { gene: index, flag: gene.flag[0] }),
+            })
+            .collect::<Result<Vec<JsonValue>, GenomeError>>()?;
+        Ok(JsonValue::object([
+            ("format", "aster_genome".into()),
+            ("version", (JSON_FORMAT_VERSION as usize).into()),
+            (
+                "config",
+                JsonValue::object([
+                    ("connection_bias", self.config.connection_bias.into()),
+                    ("inclusion_probability", self.config.inclusion_probability.into()),
+                ]),
+            ),
+            ("genes", JsonValue::Array(genes)),
+            (
+                "lineage",
+                JsonValue::object([
+                    ("id", self.lineage.id.into()),
+                    ("parents", self.lineage.parents.clone().into()),
+                    ("birth_generation", self.lineage.birth_generation.into()),
+                    ("mutations", mutation_names(&self.lineage.mutations)),
+                ]),
+            ),
+        ]))
+    }
+
+    /// Reads an `aster_genome` document and validates the genome
+    pub fn from_json_value(value: &JsonValue) -> Result<Self, JsonError> {
+        let fields = Fields::of(value, "aster_genome")?;
+        let config = Fields { value: fields.get("config")? };
+        let config = MutationConfig {
+            connection_bias: config.u64("connection_bias")? as usize,
+            inclusion_probability: config.f32("inclusion_probability")?,
+        };
+        let genes = fields
+            .array("genes")?
+            .iter()
+            .map(|gene| {
+                let gene = Fields { value: gene };
+                let gene_type = match gene.str("kind")? {
+                    "input" => GeneType::Input,
+                    "hidden" => GeneType::Hidden,
+                    "output" => GeneType::Output,
+                    "synapse" => {
+                        let status = if gene.bool("enabled")? { SynapseStatus::Enabled } else { SynapseStatus::Disabled };
+                        return Ok(Gene {
+                            id: gene.id("from")?,
+                            flag: [GeneType::Synapse.as_u8(), status as u8],
+                            local_data: convert_id_to_f32(gene.id("to")?),
+                            extern_data: gene.f32("weight")?,
+                        });
+                    }
+                    other => return Err(JsonError::Invalid(format!("unknown gene kind {:?}", other))),
+                };
+                let children = gene.u64("children")?;
+                Ok(Gene {
+                    id: gene.id("id")?,
+                    flag: [gene_type.as_u8(), u8::try_from(children).map_err(|_| Fields::wrong("children", "at most 255"))?],
+                    local_data: gene.f32("bias")?,
+                    extern_data: gene.f32("activation")?,
+                })
+            })
+            .collect::<Result<Vec<Gene>, JsonError>>()?;
+
+        let lineage = match value.get("lineage") {
+            Some(lineage) => {
+                let lineage = Fields { value: lineage };
+                Lineage {
+                    id: lineage.u64("id")?,
+                    parents: lineage.ids("parents")?,
+                    birth_generation: lineage.u64("birth_generation")? as usize,
+                    mutations: lineage.mutations("mutations")?,
+                }
+            }
+            None => Lineage::root(),
+        };
+        let genome = Genome { genes, config, lineage };
+        genome.validate().map_err(JsonError::Genome)?;
+        Lineage::reserve_id(genome.lineage.id);
+        Ok(genome)
+    }
+
+    /// Pretty-printed `aster_genome` document, see `to_json_value`
+    pub fn to_json(&self) -> Result<String, GenomeError> {
+        Ok(self.to_json_value()?.pretty())
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, JsonError> {
+        Self::from_json_value(&text.parse()?)
+    }
+}
+
+impl Network {
+    /// `aster_network` document of the network. Fails like `Genome::to_json_value` if the genome, or a
+    /// neuron or synapse built from it, has an unknown type or status.
+    pub fn to_json_value(&self) -> Result<JsonValue, GenomeError> {
+        let genome = self.genome.to_json_value()?;
+        // Neurons and synapses come from genes, so problems are reported against the gene they were built from
+        let gene_index = |id: [u8; 2], flag: u8| {
+            self.genome.genes.iter().position(|gene| gene.id == id && gene.flag[0] == flag).unwrap_or(self.genome.genes.len())
+        };
+        let neurons = self
+            .evaluation_order()
+            .iter()
+            .map(|id| {
+                let neuron = &self.neurons[id];
+                let kind = match GeneType::from_u8(neuron.flag[0]) {
+                    Some(gene_type) if gene_type != GeneType::Synapse => gene_kind(gene_type),
+                    _ => {
+                        let gene = gene_index(neuron.id, neuron.flag[0]);
+                        return Err(GenomeError::UnknownGeneType { gene, flag: neuron.flag[0] });
+                    }
+                };
+                Ok(JsonValue::object([
+                    ("id", neuron.id.into()),
+                    ("kind", kind.into()),
+                    ("bias", neuron.bias.into()),
+                    ("activation", neuron.activation.into()),
+                ]))
+            })
+            .collect::<Result<Vec<JsonValue>, GenomeError>>()?;
+        let mut synapses: Vec<_> = self.synapses.values().collect();
+        synapses.sort_by_key(|synapse| (synapse.id, synapse.destination));
+        let synapses = synapses
+            .iter()
+            .map(|synapse| {
+                let enabled = synapse_enabled(gene_index(synapse.id, synapse.flag[0]), synapse.flag[1])?;
+                Ok(JsonValue::object([
+                    ("from", synapse.id.into()),
+                    ("to", synapse.destination.into()),
+                    ("weight", synapse.weight.into()),
+                    ("enabled", enabled.into()),
+                ]))
+            })
+            .collect::<Result<Vec<JsonValue>, GenomeError>>()?;
+        Ok(JsonValue::object([
+            ("format", "aster_network".into()),
+            ("version", (JSON_FORMAT_VERSION as usize).into()),
+            ("input_count", self.input_count().into()),
+            ("output_count", self.output_count().into()),
+            ("neurons", JsonValue::Array(neurons)),
+            ("synapses", JsonValue::Array(synapses)),
+            ("genome", genome),
+        ]))
+    }
+
+    /// Reads an `aster_network` document, rebuilding the network from its genome and restoring activations
+    pub fn from_json_value(value: &JsonValue) -> Result<Self, JsonError> {
+        let fields = Fields::of(value, "aster_network")?;
+        let mut network = Network::from_genome(Genome::from_json_value(fields.get("genome")?)?);
+        for neuron in fields.array("neurons")? {
+            let neuron = Fields { value: neuron };
+            let id = neuron.id("id")?;
+            let activation = neuron.f32("activation")?;
+            network
+                .neurons
+                .get_mut(&id)
+                .ok_or_else(|| JsonError::Invalid(format!("neuron {:?} is not in the genome", id)))?
+                .activation = activation;
+        }
+        Ok(network)
+    }
+
+    /// Pretty-printed `aster_network` document, see `to_json_value`
+    pub fn to_json(&self) -> Result<String, GenomeError> {
+        Ok(self.to_json_value()?.pretty())
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, JsonError> {
+        Self::from_json_value(&text.parse()?)
+    }
+}
+
+impl GenerationSummary {
+    /// Fails like `Genome::to_json_value` if the champion cannot be exported
+    pub fn to_json_value(&self) -> Result<JsonValue, GenomeError> {
+        Ok(JsonValue::object([
+            ("generation", self.generation.into()),
+            ("population_size", self.population_size.into()),
+            ("best_fitness", self.best_fitness.into()),
+            ("mean_fitness", self.mean_fitness.into()),
+            ("median_fitness", self.median_fitness.into()),
+            ("worst_fitness", self.worst_fitness.into()),
+            ("fitness_std_dev", self.fitness_std_dev.into()),
+            ("mean_hidden_neurons", self.mean_hidden_neurons.into()),
+            ("mean_synapses", self.mean_synapses.into()),
+            ("species_sizes", JsonValue::Array(self.species_sizes.iter().map(|&size| size.into()).collect())),
+            ("champion", self.champion.to_json_value()?),
+        ]))
+    }
+
+    pub fn from_json_value(value: &JsonValue) -> Result<Self, JsonError> {
+        let fields = Fields { value };
+        let species_sizes = fields
+            .array("species_sizes")?
+            .iter()
+            .map(|size| match size {
+                JsonValue::Number(number) => number.parse().ok(),
+                _ => None,
+            })
+            .collect::<Option<Vec<usize>>>()
+            .ok_or_else(|| Fields::wrong("species_sizes", "an array of whole numbers"))?;
+        Ok(GenerationSummary {
+            generation: fields.u64("generation")? as usize,
+            population_size: fields.u64("population_size")? as usize,
+            best_fitness: fields.f32("best_fitness")?,
+            mean_fitness: fields.f32("mean_fitness")?,
+            median_fitness: fields.f32("median_fitness")?,
+            worst_fitness: fields.f32("worst_fitness")?,
+            fitness_std_dev: fields.f32("fitness_std_dev")?,
+            mean_hidden_neurons: fields.f32("mean_hidden_neurons")?,
+            mean_synapses: fields.f32("mean_synapses")?,
+            species_sizes,
+            champion: Genome::from_json_value(fields.get("champion")?)?,
+        })
+    }
+
+    /// Pretty-printed `aster_run` document of a whole run
+    pub fn run_to_json(summaries: &[GenerationSummary]) -> Result<String, GenomeError> {
+        let generations = summaries.iter().map(GenerationSummary::to_json_value).collect::<Result<_, _>>()?;
+        Ok(JsonValue::object([
+            ("format", "aster_run".into()),
+            ("version", (JSON_FORMAT_VERSION as usize).into()),
+            ("generations", JsonValue::Array(generations)),
+        ])
+        .pretty())
+    }
+
+    /// Reads an `aster_run` document
+    pub fn run_from_json(text: &str) -> Result<Vec<GenerationSummary>, JsonError> {
+        let document: JsonValue = text.parse()?;
+        Fields::of(&document, "aster_run")?
+            .array("generations")?
+            .iter()
+            .map(GenerationSummary::from_json_value)
+            .collect()
+    }
+}
+
+impl Genealogy {
+    pub fn to_json_value(&self) -> JsonValue {
+        let genomes = self
+            .entries
+            .values()
+            .map(|entry| {
+                JsonValue::object([
+                    ("id", entry.id.into()),
+                    ("parents", entry.parents.clone().into()),
+                    ("birth_generation", entry.birth_generation.into()),
+                    ("last_generation", entry.last_generation.into()),
+                    ("mutations", mutation_names(&entry.mutations)),
+                    ("fitness", entry.fitness.map_or(JsonValue::Null, JsonValue::from)),
+                ])
+            })
+            .collect();
+        JsonValue::object([
+            ("format", "aster_genealogy".into()),
+            ("version", (JSON_FORMAT_VERSION as usize).into()),
+            ("genomes", JsonValue::Array(genomes)),
+        ])
+    }
+
+    pub fn from_json_value(value: &JsonValue) -> Result<Self, JsonError> {
+        let mut genealogy = Genealogy::new();
+        for entry in Fields::of(value, "aster_genealogy")?.array("genomes")? {
+            let entry = Fields { value: entry };
+            let fitness = entry.f32("fitness")?;
+            let entry = GenealogyEntry {
+                id: entry.u64("id")?,
+                parents: entry.ids("parents")?,
+                birth_generation: entry.u64("birth_generation")? as usize,
+                last_generation: entry.u64("last_generation")? as usize,
+                mutations: entry.mutations("mutations")?,
+                fitness: if fitness.is_nan() { None } else { Some(fitness) },
+            };
+            Lineage::reserve_id(entry.id);
+            if genealogy.entries.insert(entry.id, entry).is_some() {
+                return Err(JsonError::Invalid("genome ids in a genealogy must be unique".to_string()));
+            }
+        }
+        Ok(genealogy)
+    }
+
+    /// Pretty-printed `aster_genealogy` document
+    pub fn to_json(&self) -> String {
+        self.to_json_value().pretty()
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, JsonError> {
+        Self::from_json_value(&text.parse()?)
+    }
+}
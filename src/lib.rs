@@ -1,13 +1,73 @@
-use core::f64;
 use std::collections::HashMap;
 pub mod genome;
 pub use genome::*;
 
 pub mod visuals;
-pub use visuals::*;
+pub use visuals::{NodeColor, PlotFormat, VisualizeOptions};
 
-use plotly::common::Mode;
-use plotly::{Plot, Scatter};
+pub mod dot;
+
+pub mod terminal;
+pub use terminal::TreeCharset;
+
+pub mod alignment;
+
+pub mod network;
+
+pub mod compiled;
+pub use compiled::*;
+
+pub mod maze;
+pub use maze::*;
+
+pub mod sequence;
+pub use sequence::*;
+
+pub mod forecast;
+pub use forecast::*;
+
+pub mod gridworld;
+pub use gridworld::*;
+
+pub mod boardgame;
+pub use boardgame::*;
+
+pub mod coevolution;
+pub use coevolution::*;
+
+pub mod dataset;
+pub use dataset::*;
+
+pub mod metrics;
+pub use metrics::*;
+
+pub mod glyphs;
+pub use glyphs::*;
+
+pub mod parallel;
+pub use parallel::*;
+
+pub mod remote;
+
+pub mod serialize;
+pub use serialize::*;
+
+pub mod summary;
+pub use summary::*;
+
+pub mod lineage;
+pub use lineage::*;
+
+pub mod run_plots;
+pub use run_plots::*;
+
+pub mod surface;
+
+pub mod replay;
+pub use replay::EvolutionReplay;
+
+pub mod json;
+pub use json::*;
 
 
 // Enums and constants for better readability
@@ -64,10 +124,13 @@ pub struct Gene {
 }
 
 // Genome is a blueprint for the network, later parsed into neurons and synapses
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Genome {
     pub genes: Vec<Gene>,
     pub config: MutationConfig,
+    /// Id and ancestry. Struct literals must set it, `Lineage::root()` for a genome with no history.
+    /// Only the JSON format stores it, the binary, text, code and remote formats drop it.
+    pub lineage: Lineage,
 }
 
 #[derive(Debug, Clone)]
@@ -113,7 +176,7 @@ impl Network {
 
                 let new_neuron = Neuron {
                     id: gene.id,
-                    flag: gene.flag.clone(),
+                    flag: gene.flag,
                     bias: gene.local_data,
                     activation: gene.extern_data,
                 };
@@ -122,7 +185,7 @@ impl Network {
             if gene.flag[0] == 10 {
                 let new_synapse = Synapse {
                     id: gene.id,
-                    flag: gene.flag.clone(),
+                    flag: gene.flag,
                     destination: convert_f32_to_id(gene.local_data),
                     weight: gene.extern_data,
                 };
@@ -0,0 +1,352 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::Genome;
+use plotly::{
+    common::{ColorBar, ColorScale, ColorScalePalette, DashType, HoverInfo, Line, Marker, MarkerSymbol, Mode},
+    layout::Axis,
+    Layout, Plot, Scatter,
+};
+
+/// Next unused genome id, shared by every thread so ids stay unique within a process
+static NEXT_GENOME_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Mutation applied to a genome, recorded by the mutators when they change the genome
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MutationOperator {
+    /// `random_child`
+    AddNeuron,
+    /// `rand_connected_child`
+    AddConnectedNeuron,
+    /// `random_synapse`
+    AddSynapse,
+    /// `random_recurrent_synapse`
+    AddRecurrentSynapse,
+    /// `disable_random_synapse`
+    DisableSynapse,
+    /// `enable_random_synapse`
+    EnableSynapse,
+    /// `remove_random_disabled_synapse`
+    RemoveSynapse,
+}
+
+impl MutationOperator {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MutationOperator::AddNeuron => "add_neuron",
+            MutationOperator::AddConnectedNeuron => "add_connected_neuron",
+            MutationOperator::AddSynapse => "add_synapse",
+            MutationOperator::AddRecurrentSynapse => "add_recurrent_synapse",
+            MutationOperator::DisableSynapse => "disable_synapse",
+            MutationOperator::EnableSynapse => "enable_synapse",
+            MutationOperator::RemoveSynapse => "remove_synapse",
+        }
+    }
+}
+
+impl fmt::Display for MutationOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for MutationOperator {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Ok(match name {
+            "add_neuron" => MutationOperator::AddNeuron,
+            "add_connected_neuron" => MutationOperator::AddConnectedNeuron,
+            "add_synapse" => MutationOperator::AddSynapse,
+            "add_recurrent_synapse" => MutationOperator::AddRecurrentSynapse,
+            "disable_synapse" => MutationOperator::DisableSynapse,
+            "enable_synapse" => MutationOperator::EnableSynapse,
+            "remove_synapse" => MutationOperator::RemoveSynapse,
+            _ => return Err(format!("unknown mutation operator {:?}", name)),
+        })
+    }
+}
+
+/// Identity and ancestry of a genome
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lineage {
+    /// Unique within the process. Clones share it, use `Genome::offspring` for a new individual.
+    pub id: u64,
+    /// Ids of the genomes this one was bred from, empty for founders
+    pub parents: Vec<u64>,
+    pub birth_generation: usize,
+    /// Mutations applied since birth, in order
+    pub mutations: Vec<MutationOperator>,
+}
+
+impl Lineage {
+    /// Fresh id with no parents, born in generation 0.
+    /// Genomes built by `Genome::new` or decoded from a format without lineage start here.
+    pub fn root() -> Self {
+        Lineage {
+            id: NEXT_GENOME_ID.fetch_add(1, Ordering::Relaxed),
+            parents: Vec::new(),
+            birth_generation: 0,
+            mutations: Vec::new(),
+        }
+    }
+
+    /// Marks `id` and every id below it as used, so ids handed out later cannot collide with
+    /// genomes or genealogy entries read back from a file
+    pub(crate) fn reserve_id(id: u64) {
+        NEXT_GENOME_ID.fetch_max(id.saturating_add(1), Ordering::Relaxed);
+    }
+}
+
+// Lineage of individual genomes
+impl Genome {
+    /// Prints the lineage in the style of `display`
+    pub fn display_lineage(&self) {
+        println!(
+            "LINEAGE # ID: {} # PARENTS: {:?} # BORN: {} # MUTATIONS: {:?}",
+            self.lineage.id, self.lineage.parents, self.lineage.birth_generation, self.lineage.mutations
+        );
+    }
+
+    /// Copy of this genome as a new individual: fresh id, this genome as its parent and no mutations yet
+    pub fn offspring(&self, generation: usize) -> Genome {
+        let mut child = self.clone();
+        child.record_birth(&[self], generation);
+        child
+    }
+
+    /// Gives the genome a fresh id and records its parents, for children assembled outside `offspring`
+    /// such as crossover results
+    pub fn record_birth(&mut self, parents: &[&Genome], generation: usize) {
+        self.lineage = Lineage {
+            parents: parents.iter().map(|parent| parent.lineage.id).collect(),
+            birth_generation: generation,
+            ..Lineage::root()
+        };
+    }
+}
+
+/// One genome in the genealogy log
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenealogyEntry {
+    pub id: u64,
+    pub parents: Vec<u64>,
+    pub birth_generation: usize,
+    /// Last generation the genome was recorded in
+    pub last_generation: usize,
+    pub mutations: Vec<MutationOperator>,
+    /// Best fitness the genome scored in any recorded generation
+    pub fitness: Option<f32>,
+}
+
+/// Genealogy log of a run: every genome recorded in any generation, keyed by id
+#[derive(Debug, Clone, Default)]
+pub struct Genealogy {
+    pub entries: BTreeMap<u64, GenealogyEntry>,
+}
+
+impl Genealogy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a scored population, `fitness[i]` belonging to `population[i]`.
+    /// Genomes already in the log keep their best fitness and take the latest mutation list.
+    pub fn record_generation(&mut self, generation: usize, population: &[Genome], fitness: &[f32]) {
+        assert_eq!(population.len(), fitness.len(), "Expected one fitness per genome");
+        for (genome, &score) in population.iter().zip(fitness) {
+            let lineage = &genome.lineage;
+            let entry = self.entries.entry(lineage.id).or_insert_with(|| GenealogyEntry {
+                id: lineage.id,
+                parents: lineage.parents.clone(),
+                birth_generation: lineage.birth_generation,
+                last_generation: generation,
+                mutations: Vec::new(),
+                fitness: None,
+            });
+            entry.last_generation = entry.last_generation.max(generation);
+            entry.mutations = lineage.mutations.clone();
+            if score.is_finite() && entry.fitness.is_none_or(|best| score > best) {
+                entry.fitness = Some(score);
+            }
+        }
+    }
+
+    /// The genome and all its recorded ancestors through every parent, oldest first.
+    /// Reading the mutations and fitness along this list shows which mutations a champion's success came from.
+    pub fn ancestry(&self, id: u64) -> Vec<&GenealogyEntry> {
+        let mut found = BTreeMap::new();
+        let mut pending = vec![id];
+        while let Some(id) = pending.pop() {
+            if found.contains_key(&id) {
+                continue;
+            }
+            if let Some(entry) = self.entries.get(&id) {
+                found.insert(id, entry);
+                pending.extend(&entry.parents);
+            }
+        }
+        let mut ancestry: Vec<&GenealogyEntry> = found.into_values().collect();
+        ancestry.sort_by_key(|entry| (entry.birth_generation, entry.id));
+        ancestry
+    }
+
+    /// Phylogenetic tree: genomes placed by birth generation, each under its first parent, and colored by fitness.
+    /// Lines to further parents are dotted. The ancestry of `highlight`, typically the champion, is drawn on top.
+    pub fn phylogeny_plot(&self, highlight: Option<u64>) -> Plot {
+        let rows = self.tree_rows();
+        let highlighted: Vec<u64> = highlight.map_or(Vec::new(), |id| self.ancestry(id).iter().map(|entry| entry.id).collect());
+
+        let mut plot = Plot::new();
+        let edge_trace = |first_parent: bool, on_path: bool| {
+            let (mut x, mut y) = (Vec::new(), Vec::new());
+            for entry in self.entries.values() {
+                for (index, parent) in entry.parents.iter().enumerate() {
+                    let Some(parent_entry) = self.entries.get(parent) else { continue };
+                    let path = highlighted.contains(parent) && highlighted.contains(&entry.id);
+                    if (index == 0) != first_parent || path != on_path {
+                        continue;
+                    }
+                    // Elbow from the parent's row across to the child's birth
+                    x.extend([parent_entry.birth_generation as f64, parent_entry.birth_generation as f64, entry.birth_generation as f64, f64::NAN]);
+                    y.extend([rows[parent], rows[&entry.id], rows[&entry.id], f64::NAN]);
+                }
+            }
+            (x, y)
+        };
+        let edge_styles = [
+            (true, false, "Descent", Line::new().color("#999").width(1.0)),
+            (false, false, "Second parent", Line::new().color("#999").width(1.0).dash(DashType::Dot)),
+            (true, true, "Highlighted ancestry", Line::new().color("#d93025").width(2.5)),
+            (false, true, "Highlighted ancestry", Line::new().color("#d93025").width(2.0).dash(DashType::Dot)),
+        ];
+        for (first_parent, on_path, name, line) in edge_styles {
+            let (x, y) = edge_trace(first_parent, on_path);
+            if x.is_empty() {
+                continue;
+            }
+            plot.add_trace(
+                Scatter::new(x, y)
+                    .mode(Mode::Lines)
+                    .name(name)
+                    .legend_group(name)
+                    .show_legend(first_parent)
+                    .line(line)
+                    .hover_info(HoverInfo::None),
+            );
+        }
+
+        let entries: Vec<&GenealogyEntry> = self.entries.values().collect();
+        let hover_text = entries
+            .iter()
+            .map(|entry| {
+                let parent_fitness = entry.parents.first().and_then(|parent| self.entries.get(parent)).and_then(|parent| parent.fitness);
+                let fitness = match (entry.fitness, parent_fitness) {
+                    (Some(fitness), Some(parent)) => format!("{} ({:+} over first parent)", fitness, fitness - parent),
+                    (Some(fitness), None) => fitness.to_string(),
+                    (None, _) => "not scored".to_string(),
+                };
+                let mutations: Vec<&str> = entry.mutations.iter().map(|mutation| mutation.name()).collect();
+                format!(
+                    "Genome {}<br>Parents: {:?}<br>Generations {} to {}<br>Mutations: {}<br>Fitness: {}",
+                    entry.id,
+                    entry.parents,
+                    entry.birth_generation,
+                    entry.last_generation,
+                    if mutations.is_empty() { "none".to_string() } else { mutations.join(", ") },
+                    fitness
+                )
+            })
+            .collect();
+        plot.add_trace(
+            Scatter::new(
+                entries.iter().map(|entry| entry.birth_generation as f64).collect(),
+                entries.iter().map(|entry| rows[&entry.id]).collect(),
+            )
+            .mode(Mode::Markers)
+            .name("Genomes")
+            .marker(
+                Marker::new()
+                    .size(8)
+                    .color_array(entries.iter().map(|entry| entry.fitness.map_or(f64::NAN, |fitness| fitness as f64)).collect())
+                    .color_scale(ColorScale::Palette(ColorScalePalette::Viridis))
+                    .show_scale(true)
+                    .color_bar(ColorBar::new().title("Fitness")),
+            )
+            .hover_text_array(hover_text)
+            .hover_info(HoverInfo::Text),
+        );
+        if !highlighted.is_empty() {
+            plot.add_trace(
+                Scatter::new(
+                    highlighted.iter().map(|id| self.entries[id].birth_generation as f64).collect(),
+                    highlighted.iter().map(|id| rows[id]).collect(),
+                )
+                .mode(Mode::Markers)
+                .name("Highlighted ancestry")
+                .legend_group("Highlighted ancestry")
+                .show_legend(false)
+                .marker(Marker::new().size(13).symbol(MarkerSymbol::CircleOpen).color("#d93025"))
+                .hover_info(HoverInfo::Skip),
+            );
+        }
+
+        plot.set_layout(
+            Layout::new()
+                .title("Phylogeny")
+                .x_axis(Axis::new().title("Birth generation"))
+                .y_axis(Axis::new().visible(false).show_grid(false).zero_line(false)),
+        );
+        plot
+    }
+
+    /// Row of each genome in the phylogeny: descendants through first parents form a tree,
+    /// leaves take consecutive rows in depth-first order and each genome sits at the mean row of its children
+    fn tree_rows(&self) -> HashMap<u64, f64> {
+        let mut children: BTreeMap<u64, Vec<u64>> = BTreeMap::new();
+        let mut roots = Vec::new();
+        for entry in self.entries.values() {
+            match entry.parents.first() {
+                Some(parent) if self.entries.contains_key(parent) => children.entry(*parent).or_default().push(entry.id),
+                _ => roots.push(entry.id),
+            }
+        }
+
+        // Iterative depth-first walk, deep lineages would overflow a recursive one.
+        // Genomes left unvisited sit on a parent cycle from a hand-edited log and start their own tree.
+        let mut order = Vec::new();
+        let mut visited = HashSet::new();
+        let mut walked: HashMap<u64, Vec<u64>> = HashMap::new();
+        for start in roots.into_iter().chain(self.entries.keys().copied()) {
+            let mut pending = vec![start];
+            while let Some(id) = pending.pop() {
+                if !visited.insert(id) {
+                    continue;
+                }
+                order.push(id);
+                let kids: Vec<u64> = children.get(&id).into_iter().flatten().copied().filter(|kid| !visited.contains(kid)).collect();
+                pending.extend(kids.iter().rev());
+                if !kids.is_empty() {
+                    walked.insert(id, kids);
+                }
+            }
+        }
+        let mut rows = HashMap::new();
+        let mut next_leaf = 0.0;
+        for id in &order {
+            if !walked.contains_key(id) {
+                rows.insert(*id, next_leaf);
+                next_leaf += 1.0;
+            }
+        }
+        for id in order.iter().rev() {
+            if let Some(kids) = walked.get(id) {
+                let row = kids.iter().map(|kid| rows[kid]).sum::<f64>() / kids.len() as f64;
+                rows.insert(*id, row);
+            }
+        }
+        rows
+    }
+}
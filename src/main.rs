@@ -3,14 +3,14 @@ use aster_ml::*;
 use std::*;
 
 fn main() {        
-    let mut individual1 = Network::new(2, 2);
-    let mut individual2 = Network::new(2, 2);
+    let individual1 = Network::new(2, 2);
+    let individual2 = Network::new(2, 2);
     
 
     let mut geneome1 = individual1.genome;
     let mut geneome2 = individual2.genome;
     
-    for i in 0..14 {
+    for _ in 0..14 {
         geneome1.rand_connected_child();
         geneome2.rand_connected_child();
     }
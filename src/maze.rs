@@ -0,0 +1,406 @@
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::{Genome, Network};
+use plotly::{common::{Line, MarkerSymbol, Mode}, Plot, Scatter};
+
+/// Rangefinder directions relative to the robot heading, in radians
+pub const MAZE_RANGEFINDER_ANGLES: [f64; 6] = [-FRAC_PI_2, -FRAC_PI_4, 0.0, FRAC_PI_4, FRAC_PI_2, PI];
+/// Number of pie-slice radar sensors pointing at the goal (front, left, back, right)
+pub const MAZE_RADAR_SLICES: usize = 4;
+/// Network inputs used by the maze robot: rangefinders followed by radar slices
+pub const MAZE_INPUTS: u16 = (MAZE_RANGEFINDER_ANGLES.len() + MAZE_RADAR_SLICES) as u16;
+/// Network outputs used by the maze robot: turn and speed
+pub const MAZE_OUTPUTS: u16 = 2;
+
+/// Small built-in map where the straight line to the goal leads into a dead end
+pub const DECEPTIVE_MAZE: &str = "\
+##############
+#............#
+#.##########.#
+#.#G.......#.#
+#.#######..#.#
+#...........<#
+##############
+";
+
+#[derive(Debug)]
+pub enum MazeError {
+    Io(std::io::Error),
+    Empty,
+    UnknownCell { line: usize, column: usize, found: char },
+    MissingStart,
+    MissingGoal,
+    MultipleStarts { line: usize, column: usize },
+    MultipleGoals { line: usize, column: usize },
+}
+
+impl fmt::Display for MazeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MazeError::Io(err) => write!(f, "could not read maze: {}", err),
+            MazeError::Empty => write!(f, "maze map is empty"),
+            MazeError::UnknownCell { line, column, found } => {
+                write!(f, "unknown cell {:?} at line {}, column {}", found, line, column)
+            }
+            MazeError::MissingStart => write!(f, "maze map has no start cell"),
+            MazeError::MissingGoal => write!(f, "maze map has no goal cell"),
+            MazeError::MultipleStarts { line, column } => {
+                write!(f, "second start cell at line {}, column {}", line, column)
+            }
+            MazeError::MultipleGoals { line, column } => {
+                write!(f, "second goal cell at line {}, column {}", line, column)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MazeError {}
+
+/// An axis-aligned wall segment between a wall cell and an open cell
+#[derive(Debug, Clone, Copy)]
+pub struct Wall {
+    pub from: (f64, f64),
+    pub to: (f64, f64),
+}
+
+/// A 2D maze built from a text map.
+/// Each character is a unit cell: '#' is a wall, '.' or ' ' is open, 'G' is the goal and
+/// 'S' or one of '>' '^' '<' 'v' is the start, with the arrow giving the starting heading.
+/// Short rows are padded with open cells up to the widest row. Row 0 of the map is drawn at the top, so positions use y = rows - row.
+#[derive(Debug, Clone)]
+pub struct Maze {
+    pub width: usize,
+    pub height: usize,
+    pub walls: Vec<Wall>,
+    pub start: (f64, f64),
+    pub start_heading: f64,
+    pub goal: (f64, f64),
+}
+
+#[derive(Debug, Clone)]
+pub struct MazeConfig {
+    pub max_steps: usize,
+    pub rangefinder_range: f64,
+    pub robot_radius: f64,
+    pub max_speed: f64,
+    pub max_turn: f64,
+    pub goal_radius: f64,
+}
+
+impl Default for MazeConfig {
+    fn default() -> Self {
+        MazeConfig {
+            max_steps: 400,
+            rangefinder_range: 5.0,
+            robot_radius: 0.3,
+            max_speed: 0.25,
+            max_turn: 0.3,
+            goal_radius: 0.5,
+        }
+    }
+}
+
+/// Result of running one robot through a maze
+#[derive(Debug, Clone)]
+pub struct MazeOutcome {
+    pub trajectory: Vec<(f64, f64)>,
+    pub final_position: (f64, f64),
+    pub distance_to_goal: f64,
+    pub reached_goal: bool,
+    pub steps: usize,
+    pub fitness: f32,
+}
+
+impl MazeOutcome {
+    /// Final position of the robot, used as the behavior descriptor for novelty search
+    pub fn behavior_descriptor(&self) -> [f64; 2] {
+        [self.final_position.0, self.final_position.1]
+    }
+}
+
+/// Mean distance from a behavior descriptor to its `k` nearest neighbours in an archive
+pub fn maze_novelty(descriptor: [f64; 2], archive: &[[f64; 2]], k: usize) -> f64 {
+    let mut distances: Vec<f64> = archive
+        .iter()
+        .map(|other| (descriptor[0] - other[0]).hypot(descriptor[1] - other[1]))
+        .collect();
+    distances.sort_by(|a, b| a.total_cmp(b));
+    let nearest = &distances[..k.min(distances.len())];
+    if nearest.is_empty() {
+        return 0.0;
+    }
+    nearest.iter().sum::<f64>() / nearest.len() as f64
+}
+
+impl FromStr for Maze {
+    type Err = MazeError;
+
+    fn from_str(map: &str) -> Result<Self, Self::Err> {
+        // Blank lines before and after the map are ignored, blank rows inside it are open floor
+        let lines: Vec<&str> = map.lines().collect();
+        let is_blank = |line: &&str| line.trim().is_empty();
+        let (Some(first), Some(last)) = (lines.iter().position(|line| !is_blank(line)), lines.iter().rposition(|line| !is_blank(line)))
+        else {
+            return Err(MazeError::Empty);
+        };
+        let rows: Vec<Vec<char>> = lines[first..=last].iter().map(|line| line.chars().collect()).collect();
+        let height = rows.len();
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+
+        let mut start = None;
+        let mut goal = None;
+        for (row, cells) in rows.iter().enumerate() {
+            for (column, &cell) in cells.iter().enumerate() {
+                let center = (column as f64 + 0.5, (height - row) as f64 - 0.5);
+                let heading = match cell {
+                    '#' | '.' | ' ' => continue,
+                    'G' => {
+                        if goal.is_some() {
+                            return Err(MazeError::MultipleGoals { line: first + row + 1, column: column + 1 });
+                        }
+                        goal = Some(center);
+                        continue;
+                    }
+                    'S' | '>' => 0.0,
+                    '^' => FRAC_PI_2,
+                    '<' => PI,
+                    'v' => -FRAC_PI_2,
+                    found => return Err(MazeError::UnknownCell { line: first + row + 1, column: column + 1, found }),
+                };
+                if start.is_some() {
+                    return Err(MazeError::MultipleStarts { line: first + row + 1, column: column + 1 });
+                }
+                start = Some((center, heading));
+            }
+        }
+        let (start, start_heading) = start.ok_or(MazeError::MissingStart)?;
+        let goal = goal.ok_or(MazeError::MissingGoal)?;
+
+        // Cells outside the map count as walls, so the map is always closed.
+        // Rows shorter than the widest row are padded with open floor.
+        let is_wall = |row: isize, column: isize| {
+            if row < 0 || column < 0 || row >= height as isize || column >= width as isize {
+                return true;
+            }
+            rows[row as usize].get(column as usize) == Some(&'#')
+        };
+
+        let mut walls = Vec::new();
+        for row in 0..height as isize {
+            for column in 0..width as isize {
+                if is_wall(row, column) {
+                    continue;
+                }
+                let left = column as f64;
+                let right = left + 1.0;
+                let top = (height as isize - row) as f64;
+                let bottom = top - 1.0;
+                if is_wall(row - 1, column) {
+                    walls.push(Wall { from: (left, top), to: (right, top) });
+                }
+                if is_wall(row + 1, column) {
+                    walls.push(Wall { from: (left, bottom), to: (right, bottom) });
+                }
+                if is_wall(row, column - 1) {
+                    walls.push(Wall { from: (left, bottom), to: (left, top) });
+                }
+                if is_wall(row, column + 1) {
+                    walls.push(Wall { from: (right, bottom), to: (right, top) });
+                }
+            }
+        }
+
+        Ok(Maze {
+            width,
+            height,
+            walls,
+            start,
+            start_heading,
+            goal,
+        })
+    }
+}
+
+impl Maze {
+    /// Loads a maze from a text map file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, MazeError> {
+        std::fs::read_to_string(path).map_err(MazeError::Io)?.parse()
+    }
+
+    /// The built-in `DECEPTIVE_MAZE`
+    pub fn deceptive() -> Self {
+        DECEPTIVE_MAZE.parse().expect("built-in maze is valid")
+    }
+
+    /// Creates an empty network with the inputs and outputs the maze robot expects
+    pub fn new_network() -> Network {
+        Network::from_genome(Genome::new(MAZE_INPUTS, MAZE_OUTPUTS))
+    }
+
+    /// Drives the robot with the network until it reaches the goal or runs out of steps.
+    /// Network outputs are read as turn (output 0) and forward speed (output 1), both in [-1, 1].
+    pub fn evaluate(&self, network: &mut Network, config: &MazeConfig) -> MazeOutcome {
+        assert_eq!(network.input_count(), MAZE_INPUTS as usize, "Maze robot needs {} inputs", MAZE_INPUTS);
+        assert_eq!(network.output_count(), MAZE_OUTPUTS as usize, "Maze robot needs {} outputs", MAZE_OUTPUTS);
+        network.reset();
+
+        let start_distance = distance(self.start, self.goal);
+        let mut position = self.start;
+        let mut heading = self.start_heading;
+        let mut trajectory = vec![position];
+        let mut reached_goal = false;
+        let mut steps = 0;
+
+        while steps < config.max_steps {
+            steps += 1;
+            let outputs = network.activate(&self.sensors(position, heading, config));
+            heading = wrap_angle(heading + outputs[0] as f64 * config.max_turn);
+            let speed = (outputs[1] as f64 + 1.0) / 2.0 * config.max_speed;
+            let next = (position.0 + heading.cos() * speed, position.1 + heading.sin() * speed);
+            if !self.collides(next, config.robot_radius) {
+                position = next;
+            }
+            trajectory.push(position);
+            if distance(position, self.goal) <= config.goal_radius {
+                reached_goal = true;
+                break;
+            }
+        }
+
+        let distance_to_goal = distance(position, self.goal);
+        let fitness = if reached_goal {
+            1.0
+        } else {
+            1.0 - (distance_to_goal / start_distance).min(1.0) as f32
+        };
+
+        MazeOutcome {
+            trajectory,
+            final_position: position,
+            distance_to_goal,
+            reached_goal,
+            steps,
+            fitness,
+        }
+    }
+
+    /// Sensor readings for a robot pose: rangefinders normalized to [0, 1] (1 = nothing in range),
+    /// followed by radar slices that read 1 when the goal lies in that slice
+    pub fn sensors(&self, position: (f64, f64), heading: f64, config: &MazeConfig) -> Vec<f32> {
+        let mut inputs: Vec<f32> = MAZE_RANGEFINDER_ANGLES
+            .iter()
+            .map(|angle| (self.raycast(position, heading + angle, config.rangefinder_range) / config.rangefinder_range) as f32)
+            .collect();
+
+        let bearing = (self.goal.1 - position.1).atan2(self.goal.0 - position.0) - heading;
+        // Shift by half a slice so the front slice is centered on the heading
+        let slice_width = 2.0 * PI / MAZE_RADAR_SLICES as f64;
+        let slice = ((bearing + slice_width / 2.0).rem_euclid(2.0 * PI) / slice_width) as usize;
+        for i in 0..MAZE_RADAR_SLICES {
+            inputs.push(if i == slice % MAZE_RADAR_SLICES { 1.0 } else { 0.0 });
+        }
+        inputs
+    }
+
+    /// Distance along a ray to the nearest wall, capped at `range`
+    pub fn raycast(&self, origin: (f64, f64), angle: f64, range: f64) -> f64 {
+        let direction = (angle.cos(), angle.sin());
+        self.walls
+            .iter()
+            .filter_map(|wall| ray_segment_distance(origin, direction, wall))
+            .fold(range, f64::min)
+    }
+
+    /// Whether a robot of the given radius at `position` overlaps a wall
+    pub fn collides(&self, position: (f64, f64), radius: f64) -> bool {
+        self.walls
+            .iter()
+            .any(|wall| point_segment_distance(position, wall) < radius)
+    }
+
+    /// Builds a plot of the maze walls, start, goal and the trajectory of each outcome
+    pub fn trajectory_plot(&self, outcomes: &[MazeOutcome]) -> Plot {
+        let mut plot = Plot::new();
+
+        let mut wall_x = Vec::new();
+        let mut wall_y = Vec::new();
+        for wall in &self.walls {
+            wall_x.extend([wall.from.0, wall.to.0, f64::NAN]);
+            wall_y.extend([wall.from.1, wall.to.1, f64::NAN]);
+        }
+        plot.add_trace(
+            Scatter::new(wall_x, wall_y)
+                .mode(Mode::Lines)
+                .name("Walls")
+                .line(Line::new().color("#000").width(2.0))
+                .hover_info(plotly::common::HoverInfo::None),
+        );
+
+        for (i, outcome) in outcomes.iter().enumerate() {
+            let (x, y): (Vec<f64>, Vec<f64>) = outcome.trajectory.iter().copied().unzip();
+            plot.add_trace(
+                Scatter::new(x, y)
+                    .mode(Mode::Lines)
+                    .name(format!("Robot {} (fitness {:.3})", i, outcome.fitness))
+                    .line(Line::new().width(1.0)),
+            );
+        }
+
+        plot.add_trace(
+            Scatter::new(vec![self.start.0, self.goal.0], vec![self.start.1, self.goal.1])
+                .mode(Mode::Markers)
+                .name("Start / Goal")
+                .marker(
+                    plotly::common::Marker::new()
+                        .size(12)
+                        .color_array(vec!["blue", "red"])
+                        .symbol(MarkerSymbol::Star),
+                )
+                .text_array(vec!["Start", "Goal"])
+                .hover_info(plotly::common::HoverInfo::Text),
+        );
+
+        plot
+    }
+
+    /// Opens the trajectory plot in a browser
+    pub fn visualize_trajectories(&self, outcomes: &[MazeOutcome]) {
+        self.trajectory_plot(outcomes).show();
+    }
+}
+
+// Geometry helpers
+fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    (a.0 - b.0).hypot(a.1 - b.1)
+}
+
+fn wrap_angle(angle: f64) -> f64 {
+    (angle + PI).rem_euclid(2.0 * PI) - PI
+}
+
+/// Distance along a ray to a wall, or None if the ray misses it
+fn ray_segment_distance(origin: (f64, f64), direction: (f64, f64), wall: &Wall) -> Option<f64> {
+    let edge = (wall.to.0 - wall.from.0, wall.to.1 - wall.from.1);
+    let denominator = direction.0 * edge.1 - direction.1 * edge.0;
+    if denominator.abs() < f64::EPSILON {
+        return None;
+    }
+    let offset = (wall.from.0 - origin.0, wall.from.1 - origin.1);
+    let t = (offset.0 * edge.1 - offset.1 * edge.0) / denominator;
+    let u = (offset.0 * direction.1 - offset.1 * direction.0) / denominator;
+    if t >= 0.0 && (0.0..=1.0).contains(&u) {
+        Some(t)
+    } else {
+        None
+    }
+}
+
+fn point_segment_distance(point: (f64, f64), wall: &Wall) -> f64 {
+    let edge = (wall.to.0 - wall.from.0, wall.to.1 - wall.from.1);
+    let length_squared = edge.0 * edge.0 + edge.1 * edge.1;
+    let t = (((point.0 - wall.from.0) * edge.0 + (point.1 - wall.from.1) * edge.1) / length_squared).clamp(0.0, 1.0);
+    distance(point, (wall.from.0 + t * edge.0, wall.from.1 + t * edge.1))
+}
@@ -0,0 +1,275 @@
+use crate::{Dataset, DatasetError, Network};
+
+/// Class index of one row. Multi-column rows use the largest column. Single-column rows are binary:
+/// network outputs (tanh, in [-1, 1]) are class 1 above 0.0, targets are class 1 above 0.5.
+fn class_of(row: &[f32], threshold: f32) -> usize {
+    if row.len() == 1 {
+        return (row[0] > threshold) as usize;
+    }
+    row.iter()
+        .enumerate()
+        .fold(0, |best, (i, &value)| if value > row[best] { i } else { best })
+}
+
+fn predicted_class(row: &[f32]) -> usize {
+    class_of(row, 0.0)
+}
+
+fn target_class(row: &[f32]) -> usize {
+    class_of(row, 0.5)
+}
+
+/// Classes covered by the widest row of either side, so a prediction wider than its target still has a column
+fn class_count(predictions: &[Vec<f32>], targets: &[Vec<f32>]) -> usize {
+    let widest = predictions.iter().chain(targets).map(|row| row.len()).max();
+    widest.map_or(0, |width| width.max(2))
+}
+
+/// Output probabilities: softmax for multi-output rows, (output + 1) / 2 for a single tanh output
+fn probabilities(row: &[f32]) -> Vec<f32> {
+    if row.len() == 1 {
+        let p = ((row[0] + 1.0) / 2.0).clamp(0.0, 1.0);
+        return vec![1.0 - p, p];
+    }
+    let max = row.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let exp: Vec<f32> = row.iter().map(|value| (value - max).exp()).collect();
+    let sum: f32 = exp.iter().sum();
+    exp.iter().map(|value| value / sum).collect()
+}
+
+/// Fraction of rows whose predicted class matches the target class
+pub fn accuracy(predictions: &[Vec<f32>], targets: &[Vec<f32>]) -> f32 {
+    ConfusionMatrix::new(predictions, targets).accuracy()
+}
+
+/// Mean squared error over every output of every row
+pub fn mean_squared_error(predictions: &[Vec<f32>], targets: &[Vec<f32>]) -> f32 {
+    mean_over_outputs(predictions, targets, |error| error * error)
+}
+
+/// Mean absolute error over every output of every row
+pub fn mean_absolute_error(predictions: &[Vec<f32>], targets: &[Vec<f32>]) -> f32 {
+    mean_over_outputs(predictions, targets, f32::abs)
+}
+
+fn mean_over_outputs<F: Fn(f32) -> f32>(predictions: &[Vec<f32>], targets: &[Vec<f32>], loss: F) -> f32 {
+    let mut total = 0.0;
+    let mut count = 0;
+    for (prediction, target) in predictions.iter().zip(targets) {
+        for (p, t) in prediction.iter().zip(target) {
+            total += loss(p - t);
+            count += 1;
+        }
+    }
+    if count == 0 { 0.0 } else { total / count as f32 }
+}
+
+/// Mean negative log-likelihood of the target class, using the probabilities described on `probabilities`.
+/// A target class the prediction has no output for counts as probability 0.
+pub fn log_loss(predictions: &[Vec<f32>], targets: &[Vec<f32>]) -> f32 {
+    if targets.is_empty() {
+        return 0.0;
+    }
+    let total: f32 = predictions
+        .iter()
+        .zip(targets)
+        .map(|(prediction, target)| -probabilities(prediction).get(target_class(target)).copied().unwrap_or(0.0).max(1e-7).ln())
+        .sum();
+    total / targets.len() as f32
+}
+
+/// Area under the ROC curve for a binary problem. The score is the single output,
+/// or output 1 minus output 0 for two outputs. Rows without outputs are skipped.
+/// Returns 0.5 if only one class is present.
+pub fn roc_auc(predictions: &[Vec<f32>], targets: &[Vec<f32>]) -> f32 {
+    let score = |row: &Vec<f32>| if row.len() >= 2 { Some(row[1] - row[0]) } else { row.first().copied() };
+    let mut scored: Vec<(f32, usize)> = predictions
+        .iter()
+        .zip(targets)
+        .filter_map(|(prediction, target)| Some((score(prediction)?, target_class(target))))
+        .collect();
+    scored.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    // Mann-Whitney U with average ranks for tied scores
+    let mut positive_rank_sum = 0.0;
+    let mut i = 0;
+    while i < scored.len() {
+        let mut j = i;
+        while j < scored.len() && scored[j].0 == scored[i].0 {
+            j += 1;
+        }
+        let average_rank = (i + j + 1) as f64 / 2.0;
+        positive_rank_sum += average_rank * scored[i..j].iter().filter(|(_, class)| *class == 1).count() as f64;
+        i = j;
+    }
+    let positives = scored.iter().filter(|(_, class)| *class == 1).count() as f64;
+    let negatives = scored.len() as f64 - positives;
+    if positives == 0.0 || negatives == 0.0 {
+        return 0.5;
+    }
+    ((positive_rank_sum - positives * (positives + 1.0) / 2.0) / (positives * negatives)) as f32
+}
+
+/// Counts of target class (rows) against predicted class (columns)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfusionMatrix {
+    pub counts: Vec<Vec<usize>>,
+}
+
+impl ConfusionMatrix {
+    pub fn new(predictions: &[Vec<f32>], targets: &[Vec<f32>]) -> Self {
+        let classes = class_count(predictions, targets);
+        let mut counts = vec![vec![0; classes]; classes];
+        for (prediction, target) in predictions.iter().zip(targets) {
+            counts[target_class(target)][predicted_class(prediction)] += 1;
+        }
+        ConfusionMatrix { counts }
+    }
+
+    pub fn total(&self) -> usize {
+        self.counts.iter().flatten().sum()
+    }
+
+    pub fn accuracy(&self) -> f32 {
+        let correct: usize = (0..self.counts.len()).map(|class| self.counts[class][class]).sum();
+        ratio(correct, self.total())
+    }
+
+    /// Fraction of rows predicted as `class` that really are `class`
+    pub fn precision(&self, class: usize) -> f32 {
+        let predicted: usize = self.counts.iter().map(|row| row[class]).sum();
+        ratio(self.counts[class][class], predicted)
+    }
+
+    /// Fraction of rows of `class` that were predicted as `class`
+    pub fn recall(&self, class: usize) -> f32 {
+        ratio(self.counts[class][class], self.counts[class].iter().sum())
+    }
+
+    pub fn f1(&self, class: usize) -> f32 {
+        let (precision, recall) = (self.precision(class), self.recall(class));
+        if precision + recall == 0.0 { 0.0 } else { 2.0 * precision * recall / (precision + recall) }
+    }
+
+    /// Unweighted mean F1 over all classes
+    pub fn macro_f1(&self) -> f32 {
+        if self.counts.is_empty() {
+            return 0.0;
+        }
+        (0..self.counts.len()).map(|class| self.f1(class)).sum::<f32>() / self.counts.len() as f32
+    }
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f32 {
+    if denominator == 0 { 0.0 } else { numerator as f32 / denominator as f32 }
+}
+
+/// A metric that can be used directly as a fitness function, where higher is always better.
+/// Losses are mapped to 1 / (1 + loss).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    Accuracy,
+    MacroF1,
+    MeanSquaredError,
+    MeanAbsoluteError,
+    LogLoss,
+    RocAuc,
+}
+
+impl Metric {
+    /// Raw value of the metric
+    pub fn value(&self, predictions: &[Vec<f32>], targets: &[Vec<f32>]) -> f32 {
+        match self {
+            Metric::Accuracy => accuracy(predictions, targets),
+            Metric::MacroF1 => ConfusionMatrix::new(predictions, targets).macro_f1(),
+            Metric::MeanSquaredError => mean_squared_error(predictions, targets),
+            Metric::MeanAbsoluteError => mean_absolute_error(predictions, targets),
+            Metric::LogLoss => log_loss(predictions, targets),
+            Metric::RocAuc => roc_auc(predictions, targets),
+        }
+    }
+
+    /// Metric value turned into a higher-is-better score
+    pub fn score(&self, predictions: &[Vec<f32>], targets: &[Vec<f32>]) -> f32 {
+        let value = self.value(predictions, targets);
+        match self {
+            Metric::MeanSquaredError | Metric::MeanAbsoluteError | Metric::LogLoss => 1.0 / (1.0 + value),
+            _ => value,
+        }
+    }
+
+    /// Scores a network on a dataset. Matches the fitness signature of `Dataset::cross_validate`.
+    /// Panics if the network does not fit the dataset.
+    pub fn fitness(&self, network: &mut Network, dataset: &Dataset) -> f32 {
+        let predictions = dataset.predict(network).expect("Network does not fit the dataset");
+        self.score(&predictions, &dataset.targets)
+    }
+}
+
+/// All metrics of a network on a dataset, printable next to `Genome::statistics`
+#[derive(Debug, Clone)]
+pub struct MetricsReport {
+    pub class_names: Vec<String>,
+    pub confusion: ConfusionMatrix,
+    pub accuracy: f32,
+    pub mse: f32,
+    pub mae: f32,
+    pub log_loss: f32,
+    /// Only for binary problems
+    pub roc_auc: Option<f32>,
+}
+
+impl MetricsReport {
+    /// Fails if the network does not fit the dataset
+    pub fn new(network: &mut Network, dataset: &Dataset) -> Result<Self, DatasetError> {
+        let predictions = dataset.predict(network)?;
+        Ok(Self::from_predictions(&predictions, &dataset.targets, &dataset.class_names))
+    }
+
+    pub fn from_predictions(predictions: &[Vec<f32>], targets: &[Vec<f32>], class_names: &[String]) -> Self {
+        let confusion = ConfusionMatrix::new(predictions, targets);
+        let class_names = if class_names.len() == confusion.counts.len() {
+            class_names.to_vec()
+        } else {
+            (0..confusion.counts.len()).map(|class| class.to_string()).collect()
+        };
+        MetricsReport {
+            class_names,
+            accuracy: confusion.accuracy(),
+            roc_auc: (confusion.counts.len() == 2).then(|| roc_auc(predictions, targets)),
+            confusion,
+            mse: mean_squared_error(predictions, targets),
+            mae: mean_absolute_error(predictions, targets),
+            log_loss: log_loss(predictions, targets),
+        }
+    }
+
+    /// Prints the report
+    pub fn display(&self) {
+        println!("##### Metrics Report #####");
+        println!("Rows: {}", self.confusion.total());
+        println!("Accuracy: {:.4}", self.accuracy);
+        println!("MSE: {:.4}", self.mse);
+        println!("MAE: {:.4}", self.mae);
+        println!("Log-loss: {:.4}", self.log_loss);
+        if let Some(auc) = self.roc_auc {
+            println!("ROC-AUC: {:.4}", auc);
+        }
+        println!("Per class:");
+        for (class, name) in self.class_names.iter().enumerate() {
+            println!(
+                "  {} # PRECISION: {:.4} # RECALL: {:.4} # F1: {:.4}",
+                name,
+                self.confusion.precision(class),
+                self.confusion.recall(class),
+                self.confusion.f1(class)
+            );
+        }
+        println!("Confusion matrix (rows: target, columns: predicted):");
+        for (row, name) in self.confusion.counts.iter().zip(&self.class_names) {
+            let cells: Vec<String> = row.iter().map(|count| format!("{:>5}", count)).collect();
+            println!("  {:>10} {}", name, cells.join(""));
+        }
+        println!("##########################");
+    }
+}
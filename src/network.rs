@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+
+use crate::{get_neuron_height, GeneType, Genome, Network, SynapseStatus};
+
+// EVALUATION FUNCTIONS
+impl Network {
+    /// Creates a network from an existing genome and parses it into neurons and synapses
+    pub fn from_genome(genome: Genome) -> Self {
+        let mut network = Network {
+            genome,
+            neurons: Default::default(),
+            synapses: Default::default(),
+        };
+        network.parse_genome();
+        network
+    }
+
+    /// Number of input neurons, i.e. the length `activate` expects
+    pub fn input_count(&self) -> usize {
+        self.count_neurons(GeneType::Input)
+    }
+
+    /// Number of output neurons, i.e. the length `activate` returns
+    pub fn output_count(&self) -> usize {
+        self.count_neurons(GeneType::Output)
+    }
+
+    /// Runs one step of the network.
+    /// Neurons are updated inputs first, then hidden neurons by height, then outputs.
+    /// A synapse whose source is updated after its destination reads the source's activation
+    /// from the previous step, so activations persist between calls until `reset` is called.
+    pub fn activate(&mut self, inputs: &[f32]) -> Vec<f32> {
+        assert_eq!(
+            inputs.len(),
+            self.input_count(),
+            "Expected {} inputs, got {}",
+            self.input_count(),
+            inputs.len()
+        );
+
+        for id in self.evaluation_order() {
+            let neuron = &self.neurons[&id];
+            let activation = if neuron.flag[0] == GeneType::Input.as_u8() {
+                inputs[id[0] as usize]
+            } else {
+                // Sum in source ID order so results do not depend on HashMap iteration order
+                let mut incoming: Vec<_> = self
+                    .synapses
+                    .values()
+                    .filter(|synapse| {
+                        synapse.destination == id && synapse.flag[1] == SynapseStatus::Enabled as u8
+                    })
+                    .collect();
+                incoming.sort_by_key(|synapse| synapse.id);
+                let sum: f32 = incoming
+                    .iter()
+                    .map(|synapse| synapse.weight * self.neurons.get(&synapse.id).map_or(0.0, |n| n.activation))
+                    .sum();
+                (neuron.bias + sum).tanh()
+            };
+            self.neurons.get_mut(&id).unwrap().activation = activation;
+        }
+
+        self.outputs()
+    }
+
+    /// Runs one step from a reset state for every row, without touching the network's own activations.
+    /// Each result equals calling `reset` then `activate` on that row. The synapses are resolved once
+    /// and each neuron is computed for the whole batch before moving on to the next one.
+    pub fn activate_batch<R: AsRef<[f32]>>(&self, inputs: &[R]) -> Vec<Vec<f32>> {
+        let input_count = self.input_count();
+        for row in inputs {
+            assert_eq!(row.as_ref().len(), input_count, "Expected {} inputs, got {}", input_count, row.as_ref().len());
+        }
+
+        let order = self.evaluation_order();
+        let index: HashMap<[u8; 2], usize> = order.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        let mut incoming: Vec<Vec<(usize, f32)>> = vec![Vec::new(); order.len()];
+        for synapse in self.synapses.values() {
+            if synapse.flag[1] != SynapseStatus::Enabled as u8 {
+                continue;
+            }
+            if let (Some(&from), Some(&to)) = (index.get(&synapse.id), index.get(&synapse.destination)) {
+                incoming[to].push((from, synapse.weight));
+            }
+        }
+        for edges in incoming.iter_mut() {
+            edges.sort_by_key(|&(from, _)| order[from]);
+        }
+
+        // activations[neuron][row], neurons not yet computed this step read as 0.0 like after a reset
+        let mut activations = vec![vec![0.0f32; inputs.len()]; order.len()];
+        for (i, id) in order.iter().enumerate() {
+            let neuron = &self.neurons[id];
+            let mut column = vec![0.0f32; inputs.len()];
+            if neuron.flag[0] == GeneType::Input.as_u8() {
+                for (value, row) in column.iter_mut().zip(inputs) {
+                    *value = row.as_ref()[id[0] as usize];
+                }
+            } else {
+                for &(from, weight) in &incoming[i] {
+                    for (value, source) in column.iter_mut().zip(&activations[from]) {
+                        *value += weight * source;
+                    }
+                }
+                column.iter_mut().for_each(|value| *value = (neuron.bias + *value).tanh());
+            }
+            activations[i] = column;
+        }
+
+        let outputs: Vec<usize> = (0..self.output_count())
+            .map(|output| index[&[255 - output as u8, 0]])
+            .collect();
+        (0..inputs.len())
+            .map(|row| outputs.iter().map(|&neuron| activations[neuron][row]).collect())
+            .collect()
+    }
+
+    /// Returns the current output activations, ordered by output index
+    pub fn outputs(&self) -> Vec<f32> {
+        (0..self.output_count())
+            .map(|i| self.neurons.get(&[255 - i as u8, 0]).map_or(0.0, |n| n.activation))
+            .collect()
+    }
+
+    /// Clears all activations, forgetting any state carried between steps
+    pub fn reset(&mut self) {
+        for neuron in self.neurons.values_mut() {
+            neuron.activation = 0.0;
+        }
+    }
+
+    /// Neuron IDs in the order `activate` updates them
+    pub fn evaluation_order(&self) -> Vec<[u8; 2]> {
+        let mut order: Vec<[u8; 2]> = self.neurons.keys().copied().collect();
+        order.sort_by_key(|id| {
+            let rank = match GeneType::from_u8(self.neurons[id].flag[0]) {
+                Some(GeneType::Input) => 0,
+                Some(GeneType::Output) => 2,
+                _ => 1,
+            };
+            (rank, get_neuron_height(id[1]), *id)
+        });
+        order
+    }
+
+    /// Whether any enabled synapse points back to a neuron updated no later than its source,
+    /// i.e. whether the network can carry state from one step to the next.
+    /// Synapses with a missing source or destination carry nothing and are not recurrent.
+    pub fn is_recurrent(&self) -> bool {
+        let order = self.evaluation_order();
+        let position = |id: &[u8; 2]| order.iter().position(|other| other == id);
+        self.synapses.values().any(|synapse| {
+            synapse.flag[1] == SynapseStatus::Enabled as u8
+                && matches!(
+                    (position(&synapse.destination), position(&synapse.id)),
+                    (Some(destination), Some(source)) if destination <= source
+                )
+        })
+    }
+
+    fn count_neurons(&self, gene_type: GeneType) -> usize {
+        self.neurons
+            .values()
+            .filter(|neuron| neuron.flag[0] == gene_type.as_u8())
+            .count()
+    }
+}
@@ -0,0 +1,72 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use crate::Genome;
+use rand::prelude::*;
+use rand::rngs::StdRng;
+
+/// Evaluates a population on several threads with results that do not depend on the thread count.
+///
+/// Every individual gets its own RNG stream seeded from (run seed, generation, index), and results
+/// are stored by index, so the same fitness function gives bit-identical results with 1 or 64 workers.
+/// The fitness function must take all of its randomness from the RNG it is given.
+#[derive(Debug, Clone)]
+pub struct ParallelEvaluator {
+    /// Number of worker threads, 0 uses all available cores
+    pub workers: usize,
+    pub seed: u64,
+}
+
+impl ParallelEvaluator {
+    pub fn new(workers: usize, seed: u64) -> Self {
+        ParallelEvaluator { workers, seed }
+    }
+
+    /// Worker threads actually used
+    pub fn worker_count(&self) -> usize {
+        if self.workers > 0 {
+            return self.workers;
+        }
+        thread::available_parallelism().map_or(1, |count| count.get())
+    }
+
+    /// Seed of the RNG stream given to one individual
+    pub fn individual_seed(&self, generation: usize, index: usize) -> u64 {
+        splitmix64(splitmix64(self.seed ^ splitmix64(generation as u64)) ^ index as u64)
+    }
+
+    /// Scores every genome, returning fitness in population order
+    pub fn evaluate<F>(&self, generation: usize, genomes: &[Genome], fitness: F) -> Vec<f32>
+    where
+        F: Fn(&Genome, &mut StdRng) -> f32 + Sync,
+    {
+        let next = AtomicUsize::new(0);
+        let results = Mutex::new(vec![0.0; genomes.len()]);
+        let workers = self.worker_count().min(genomes.len()).max(1);
+
+        thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    if index >= genomes.len() {
+                        break;
+                    }
+                    let mut rng = StdRng::seed_from_u64(self.individual_seed(generation, index));
+                    let score = fitness(&genomes[index], &mut rng);
+                    results.lock().unwrap()[index] = score;
+                });
+            }
+        });
+
+        results.into_inner().unwrap()
+    }
+}
+
+/// SplitMix64 finalizer, spreads nearby inputs into unrelated seeds
+fn splitmix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
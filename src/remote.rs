@@ -0,0 +1,427 @@
+//! Out-of-process evaluation over a line-based protocol.
+//!
+//! A worker is a separate process, reached through its stdin/stdout, a TCP socket or a Unix socket.
+//! Messages are UTF-8 lines, fields separated by single spaces, numbers written in Rust's
+//! shortest round-trip form. `>` is evaluator to worker, `<` is worker to evaluator.
+//!
+//! ```text
+//! < READY                                  sent once by the worker when a connection opens
+//!
+//! Genome evaluation, the worker builds the network and scores it:
+//! > GENOME <gene_count>
+//! > GENE <id0> <id1> <flag0> <flag1> <local_data> <extern_data>     gene_count times
+//! < FITNESS <value>
+//!
+//! Streaming episode, the evaluator runs the network and the worker runs the environment:
+//! > EPISODE <input_count> <output_count>
+//! < OBS <value> ...                        input_count values
+//! > ACT <value> ...                        output_count values, the network's outputs
+//!   (OBS / ACT repeat until the episode ends)
+//! < DONE <fitness>
+//!
+//! < ERROR <message>                        the worker could not handle the request
+//! > QUIT                                   the worker closes the connection
+//! ```
+//!
+//! The evaluator waits at most `timeout` for each line. A timeout, a closed connection or a
+//! malformed reply drops the connection (killing a process worker, shutting down a socket),
+//! reconnects and retries the request up to `retries` more times. `ERROR` replies also drop the
+//! connection but are returned without retrying.
+//!
+//! Only genes cross the connection. The worker receives default mutation settings and a fresh
+//! `Lineage::root`, and nothing it does to the genome comes back to the evaluator.
+
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpStream};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::{Gene, Genome, Lineage, MutationConfig, Network};
+
+#[derive(Debug)]
+pub enum WorkerError {
+    Connect(io::Error),
+    Io(io::Error),
+    Timeout,
+    /// The worker closed the connection or exited
+    Crashed,
+    Protocol(String),
+    /// The worker answered with ERROR
+    Worker(String),
+    RetriesExhausted { attempts: usize, last: Box<WorkerError> },
+}
+
+impl fmt::Display for WorkerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WorkerError::Connect(err) => write!(f, "could not start or connect to worker: {}", err),
+            WorkerError::Io(err) => write!(f, "worker connection failed: {}", err),
+            WorkerError::Timeout => write!(f, "worker timed out"),
+            WorkerError::Crashed => write!(f, "worker closed the connection"),
+            WorkerError::Protocol(message) => write!(f, "protocol error: {}", message),
+            WorkerError::Worker(message) => write!(f, "worker error: {}", message),
+            WorkerError::RetriesExhausted { attempts, last } => {
+                write!(f, "gave up after {} attempts: {}", attempts, last)
+            }
+        }
+    }
+}
+
+impl std::error::Error for WorkerError {}
+
+/// How to reach a worker
+#[derive(Debug, Clone)]
+pub enum WorkerSpec {
+    /// Spawns the program and talks to it over stdin/stdout
+    Process { program: PathBuf, args: Vec<String> },
+    /// Connects to a worker listening on a TCP address
+    Tcp(String),
+    /// Connects to a worker listening on a Unix socket
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+/// Handle kept on socket connections so dropping one also closes the reader thread's clone
+enum Socket {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(std::os::unix::net::UnixStream),
+}
+
+impl Socket {
+    fn shutdown(&self) -> io::Result<()> {
+        match self {
+            Socket::Tcp(stream) => stream.shutdown(Shutdown::Both),
+            #[cfg(unix)]
+            Socket::Unix(stream) => stream.shutdown(Shutdown::Both),
+        }
+    }
+}
+
+struct WorkerConnection {
+    lines: Receiver<io::Result<String>>,
+    writer: Box<dyn Write + Send>,
+    child: Option<Child>,
+    socket: Option<Socket>,
+}
+
+impl Drop for WorkerConnection {
+    fn drop(&mut self) {
+        if let Some(child) = self.child.as_mut() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        if let Some(socket) = self.socket.as_ref() {
+            let _ = socket.shutdown();
+        }
+    }
+}
+
+impl WorkerConnection {
+    fn open(spec: &WorkerSpec) -> Result<Self, WorkerError> {
+        type Parts = (Box<dyn Read + Send>, Box<dyn Write + Send>, Option<Child>, Option<Socket>);
+        let (reader, writer, child, socket): Parts = match spec {
+            WorkerSpec::Process { program, args } => {
+                let mut child = Command::new(program)
+                    .args(args)
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::piped())
+                    .spawn()
+                    .map_err(WorkerError::Connect)?;
+                let stdin = child.stdin.take().expect("stdin is piped");
+                let stdout = child.stdout.take().expect("stdout is piped");
+                (Box::new(stdout), Box::new(stdin), Some(child), None)
+            }
+            WorkerSpec::Tcp(address) => {
+                let stream = TcpStream::connect(address).map_err(WorkerError::Connect)?;
+                let reader = stream.try_clone().map_err(WorkerError::Connect)?;
+                let socket = Socket::Tcp(stream.try_clone().map_err(WorkerError::Connect)?);
+                (Box::new(reader), Box::new(stream), None, Some(socket))
+            }
+            #[cfg(unix)]
+            WorkerSpec::Unix(path) => {
+                let stream = std::os::unix::net::UnixStream::connect(path).map_err(WorkerError::Connect)?;
+                let reader = stream.try_clone().map_err(WorkerError::Connect)?;
+                let socket = Socket::Unix(stream.try_clone().map_err(WorkerError::Connect)?);
+                (Box::new(reader), Box::new(stream), None, Some(socket))
+            }
+        };
+
+        // A reader thread turns the blocking stream into a channel so every read can time out
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(reader).lines() {
+                let failed = line.is_err();
+                if sender.send(line).is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        Ok(WorkerConnection { lines, writer, child, socket })
+    }
+
+    fn send(&mut self, line: &str) -> Result<(), WorkerError> {
+        writeln!(self.writer, "{}", line)
+            .and_then(|_| self.writer.flush())
+            .map_err(|err| match err.kind() {
+                io::ErrorKind::BrokenPipe => WorkerError::Crashed,
+                _ => WorkerError::Io(err),
+            })
+    }
+
+    fn receive(&mut self, timeout: Duration) -> Result<String, WorkerError> {
+        match self.lines.recv_timeout(timeout) {
+            Ok(Ok(line)) => Ok(line),
+            Ok(Err(err)) => Err(WorkerError::Io(err)),
+            Err(RecvTimeoutError::Timeout) => Err(WorkerError::Timeout),
+            Err(RecvTimeoutError::Disconnected) => Err(WorkerError::Crashed),
+        }
+    }
+
+    /// Reads a reply and splits off its keyword, turning ERROR into `WorkerError::Worker`
+    fn expect(&mut self, timeout: Duration, keywords: &[&str]) -> Result<(String, Vec<String>), WorkerError> {
+        let line = self.receive(timeout)?;
+        let mut fields = line.split_whitespace().map(str::to_string);
+        let keyword = fields.next().unwrap_or_default();
+        if keyword == "ERROR" {
+            return Err(WorkerError::Worker(line.trim_start_matches("ERROR").trim().to_string()));
+        }
+        if !keywords.contains(&keyword.as_str()) {
+            return Err(WorkerError::Protocol(format!("expected {}, got {:?}", keywords.join(" or "), line)));
+        }
+        Ok((keyword, fields.collect()))
+    }
+}
+
+/// Sends genomes or episodes to a worker, reconnecting and retrying on failures
+pub struct RemoteEvaluator {
+    pub spec: WorkerSpec,
+    pub timeout: Duration,
+    pub retries: usize,
+    connection: Option<WorkerConnection>,
+}
+
+impl RemoteEvaluator {
+    pub fn new(spec: WorkerSpec) -> Self {
+        RemoteEvaluator {
+            spec,
+            timeout: Duration::from_secs(10),
+            retries: 2,
+            connection: None,
+        }
+    }
+
+    /// Sends a genome and returns the fitness the worker computed
+    pub fn evaluate_genome(&mut self, genome: &Genome) -> Result<f32, WorkerError> {
+        let timeout = self.timeout;
+        self.with_retries(|connection| {
+            connection.send(&format!("GENOME {}", genome.genes.len()))?;
+            for gene in &genome.genes {
+                connection.send(&encode_gene(gene))?;
+            }
+            let (_, fields) = connection.expect(timeout, &["FITNESS"])?;
+            parse_values(&fields, 1).map(|values| values[0])
+        })
+    }
+
+    /// Runs one episode of the worker's environment with the network choosing the actions.
+    /// The network is reset at the start of every attempt.
+    pub fn evaluate_streaming(&mut self, network: &mut Network) -> Result<f32, WorkerError> {
+        let timeout = self.timeout;
+        let (inputs, outputs) = (network.input_count(), network.output_count());
+        self.with_retries(|connection| {
+            network.reset();
+            connection.send(&format!("EPISODE {} {}", inputs, outputs))?;
+            loop {
+                let (keyword, fields) = connection.expect(timeout, &["OBS", "DONE"])?;
+                if keyword == "DONE" {
+                    return parse_values(&fields, 1).map(|values| values[0]);
+                }
+                let action = network.activate(&parse_values(&fields, inputs)?);
+                connection.send(&format!("ACT {}", join_values(&action)))?;
+            }
+        })
+    }
+
+    /// Asks the worker to close the connection. A process worker gets `timeout` to exit before it is killed.
+    pub fn shutdown(&mut self) {
+        if let Some(mut connection) = self.connection.take() {
+            let _ = connection.send("QUIT");
+            if let Some(child) = connection.child.as_mut() {
+                let deadline = Instant::now() + self.timeout;
+                while matches!(child.try_wait(), Ok(None)) && Instant::now() < deadline {
+                    thread::sleep(Duration::from_millis(5));
+                }
+            }
+            // Dropping the connection kills and reaps a worker that is still running
+        }
+    }
+
+    fn with_retries<T, F>(&mut self, mut request: F) -> Result<T, WorkerError>
+    where
+        F: FnMut(&mut WorkerConnection) -> Result<T, WorkerError>,
+    {
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let result = self.connect().and_then(&mut request);
+            if result.is_err() {
+                // The connection may be mid-message, start over with a fresh one
+                self.connection = None;
+            }
+            match result {
+                Ok(value) => return Ok(value),
+                Err(err @ WorkerError::Worker(_)) => return Err(err),
+                Err(err) => {
+                    if attempts > self.retries {
+                        return Err(WorkerError::RetriesExhausted { attempts, last: Box::new(err) });
+                    }
+                }
+            }
+        }
+    }
+
+    fn connect(&mut self) -> Result<&mut WorkerConnection, WorkerError> {
+        if self.connection.is_none() {
+            let mut connection = WorkerConnection::open(&self.spec)?;
+            connection.expect(self.timeout, &["READY"])?;
+            self.connection = Some(connection);
+        }
+        Ok(self.connection.as_mut().expect("connection was just opened"))
+    }
+}
+
+impl Drop for RemoteEvaluator {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+// WORKER SIDE
+
+/// Result of one environment step on the worker side
+#[derive(Debug, Clone, PartialEq)]
+pub enum EnvironmentStep {
+    Observation(Vec<f32>),
+    Done(f32),
+}
+
+/// What a worker does for each request. Errors are sent back as ERROR lines.
+pub trait WorkerHandler {
+    fn evaluate_genome(&mut self, genome: Genome) -> Result<f32, String>;
+    /// Starts an episode and returns the first observation
+    fn reset(&mut self, input_count: usize, output_count: usize) -> Result<Vec<f32>, String>;
+    fn step(&mut self, action: &[f32]) -> Result<EnvironmentStep, String>;
+}
+
+/// Serves requests on one connection until QUIT or end of input
+pub fn serve_worker<R: BufRead, W: Write, H: WorkerHandler>(reader: R, mut writer: W, handler: &mut H) -> io::Result<()> {
+    writeln!(writer, "READY")?;
+    writer.flush()?;
+    let mut lines = reader.lines();
+    while let Some(line) = lines.next() {
+        let line = line?;
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let reply = match fields.first().copied() {
+            Some("QUIT") => return Ok(()),
+            Some("GENOME") => read_genome(&fields, &mut lines).and_then(|genome| handler.evaluate_genome(genome)).map(|fitness| format!("FITNESS {}", fitness)),
+            Some("EPISODE") => run_episode(&fields, &mut lines, &mut writer, handler).map(|fitness| format!("DONE {}", fitness)),
+            Some(other) => Err(format!("unknown request {}", other)),
+            None => continue,
+        };
+        match reply {
+            Ok(reply) => writeln!(writer, "{}", reply)?,
+            Err(message) => writeln!(writer, "ERROR {}", message)?,
+        }
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+fn read_genome<I: Iterator<Item = io::Result<String>>>(fields: &[&str], lines: &mut I) -> Result<Genome, String> {
+    let count: usize = fields.get(1).and_then(|count| count.parse().ok()).ok_or("GENOME needs a gene count")?;
+    let mut genes = Vec::with_capacity(count);
+    for _ in 0..count {
+        let line = lines.next().ok_or("connection closed inside GENOME")?.map_err(|err| err.to_string())?;
+        genes.push(decode_gene(&line)?);
+    }
+    Ok(Genome {
+        genes,
+        config: MutationConfig::default(),
+        lineage: Lineage::root(),
+    })
+}
+
+fn run_episode<I, W, H>(fields: &[&str], lines: &mut I, writer: &mut W, handler: &mut H) -> Result<f32, String>
+where
+    I: Iterator<Item = io::Result<String>>,
+    W: Write,
+    H: WorkerHandler,
+{
+    let count = |index: usize| fields.get(index).and_then(|value| value.parse::<usize>().ok());
+    let (Some(inputs), Some(outputs)) = (count(1), count(2)) else {
+        return Err("EPISODE needs input and output counts".to_string());
+    };
+    let mut observation = handler.reset(inputs, outputs)?;
+    loop {
+        writeln!(writer, "OBS {}", join_values(&observation)).and_then(|_| writer.flush()).map_err(|err| err.to_string())?;
+        let line = lines.next().ok_or("connection closed inside EPISODE")?.map_err(|err| err.to_string())?;
+        let fields: Vec<String> = line.split_whitespace().map(str::to_string).collect();
+        if fields.first().map(String::as_str) != Some("ACT") {
+            return Err(format!("expected ACT, got {:?}", line));
+        }
+        let action = parse_values(&fields[1..], outputs).map_err(|err| err.to_string())?;
+        match handler.step(&action)? {
+            EnvironmentStep::Observation(next) => observation = next,
+            EnvironmentStep::Done(fitness) => return Ok(fitness),
+        }
+    }
+}
+
+// Line encoding helpers
+
+fn encode_gene(gene: &Gene) -> String {
+    format!(
+        "GENE {} {} {} {} {} {}",
+        gene.id[0], gene.id[1], gene.flag[0], gene.flag[1], gene.local_data, gene.extern_data
+    )
+}
+
+fn decode_gene(line: &str) -> Result<Gene, String> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    if fields.len() != 7 || fields[0] != "GENE" {
+        return Err(format!("malformed gene line {:?}", line));
+    }
+    let byte = |index: usize| fields[index].parse::<u8>().map_err(|_| format!("bad byte {:?} in {:?}", fields[index], line));
+    let float = |index: usize| fields[index].parse::<f32>().map_err(|_| format!("bad number {:?} in {:?}", fields[index], line));
+    Ok(Gene {
+        id: [byte(1)?, byte(2)?],
+        flag: [byte(3)?, byte(4)?],
+        local_data: float(5)?,
+        extern_data: float(6)?,
+    })
+}
+
+fn join_values(values: &[f32]) -> String {
+    values.iter().map(|value| value.to_string()).collect::<Vec<String>>().join(" ")
+}
+
+fn parse_values<S: AsRef<str>>(fields: &[S], expected: usize) -> Result<Vec<f32>, WorkerError> {
+    if fields.len() != expected {
+        return Err(WorkerError::Protocol(format!("expected {} values, got {}", expected, fields.len())));
+    }
+    fields
+        .iter()
+        .map(|field| {
+            field
+                .as_ref()
+                .parse::<f32>()
+                .map_err(|_| WorkerError::Protocol(format!("bad number {:?}", field.as_ref())))
+        })
+        .collect()
+}
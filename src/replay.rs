@@ -0,0 +1,432 @@
+use std::collections::{BTreeSet, HashMap};
+use std::io;
+use std::path::Path;
+
+use crate::{convert_f32_to_id, GeneType, GenerationSummary, Genome, JsonValue, PlotFormat, SynapseStatus, VisualizeOptions};
+
+/// plotly.js build loaded by the replay page
+const PLOTLY_CDN: &str = "https://cdn.plot.ly/plotly-2.12.1.min.js";
+/// Opacity of a disabled synapse, an absent neuron or synapse is fully transparent
+const DISABLED_OPACITY: f64 = 0.35;
+
+/// Champion genomes recorded generation by generation, played back as an animated tree plot
+#[derive(Debug, Clone)]
+pub struct EvolutionReplay {
+    /// Generation and champion, in recording order
+    pub champions: Vec<(usize, Genome)>,
+    /// In-between frames used to fade neurons and synapses in and out between two generations
+    pub fade_steps: usize,
+    /// Playback time per generation, including its fade
+    pub generation_duration_ms: usize,
+}
+
+impl Default for EvolutionReplay {
+    fn default() -> Self {
+        EvolutionReplay {
+            champions: Vec::new(),
+            fade_steps: 4,
+            generation_duration_ms: 600,
+        }
+    }
+}
+
+/// Drawn state of a synapse in one generation
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SynapseState {
+    Absent,
+    Enabled(f32),
+    Disabled(f32),
+}
+
+impl SynapseState {
+    fn opacity(&self) -> f64 {
+        match self {
+            SynapseState::Absent => 0.0,
+            SynapseState::Enabled(_) => 1.0,
+            SynapseState::Disabled(_) => DISABLED_OPACITY,
+        }
+    }
+}
+
+/// Neurons and synapses of one recorded champion
+struct Snapshot {
+    neurons: HashMap<[u8; 2], (GeneType, f32)>,
+    synapses: HashMap<([u8; 2], [u8; 2]), SynapseState>,
+}
+
+impl Snapshot {
+    fn new(genome: &Genome) -> Self {
+        let mut snapshot = Snapshot {
+            neurons: HashMap::new(),
+            synapses: HashMap::new(),
+        };
+        for gene in &genome.genes {
+            match GeneType::from_u8(gene.flag[0]) {
+                Some(GeneType::Synapse) => {
+                    let state = if gene.flag[1] == SynapseStatus::Enabled as u8 {
+                        SynapseState::Enabled(gene.extern_data)
+                    } else {
+                        SynapseState::Disabled(gene.extern_data)
+                    };
+                    snapshot.synapses.insert((gene.id, convert_f32_to_id(gene.local_data)), state);
+                }
+                Some(gene_type) => {
+                    snapshot.neurons.insert(gene.id, (gene_type, gene.local_data));
+                }
+                None => {}
+            }
+        }
+        snapshot
+    }
+
+    fn synapse(&self, key: &([u8; 2], [u8; 2])) -> SynapseState {
+        self.synapses.get(key).copied().unwrap_or(SynapseState::Absent)
+    }
+}
+
+impl EvolutionReplay {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replay of the champions of a run log
+    pub fn from_summaries(summaries: &[GenerationSummary]) -> Self {
+        let mut replay = Self::new();
+        for summary in summaries {
+            replay.record(summary.generation, &summary.champion);
+        }
+        replay
+    }
+
+    /// Records the champion of a generation
+    pub fn record(&mut self, generation: usize, champion: &Genome) {
+        self.champions.push((generation, champion.clone()));
+    }
+
+    /// Plotly figure with `data`, `layout` and `frames`.
+    /// Every neuron and synapse that appears in any champion is placed by `Genome::tree_layout` and drawn in
+    /// every frame, transparent while it is absent, so neurons and synapses fade in as they are added,
+    /// out as they are removed, and dim when a synapse is disabled. Only the title, size, `hide_axes`,
+    /// `node_labels` and `weighted_edges` options apply. A replay without champions gives a titled figure
+    /// with no data or frames.
+    ///
+    /// Key frames are named by recording index and fade frames `<index>.<step>`, so a generation
+    /// recorded twice still gets its own frames. The slider shows the generation.
+    pub fn to_figure(&self, options: &VisualizeOptions) -> JsonValue {
+        if self.champions.is_empty() {
+            let title = options.title.clone().unwrap_or_else(|| "Champion by generation".to_string());
+            return JsonValue::object([
+                ("data", JsonValue::Array(Vec::new())),
+                ("layout", JsonValue::object([("title", JsonValue::object([("text", title.into())]))])),
+                ("frames", JsonValue::Array(Vec::new())),
+            ]);
+        }
+        let snapshots: Vec<Snapshot> = self.champions.iter().map(|(_, genome)| Snapshot::new(genome)).collect();
+
+        let mut positions = HashMap::new();
+        for (_, genome) in &self.champions {
+            positions.extend(genome.tree_layout());
+        }
+        let mut neurons: Vec<[u8; 2]> = positions.keys().copied().collect();
+        neurons.sort();
+        let synapses: Vec<([u8; 2], [u8; 2])> = snapshots
+            .iter()
+            .flat_map(|snapshot| snapshot.synapses.keys().copied())
+            .filter(|(from, to)| positions.contains_key(from) && positions.contains_key(to))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let max_weight = snapshots
+            .iter()
+            .flat_map(|snapshot| snapshot.synapses.values())
+            .map(|state| match state {
+                SynapseState::Enabled(weight) | SynapseState::Disabled(weight) => weight.abs(),
+                SynapseState::Absent => 0.0,
+            })
+            .fold(0.0f32, f32::max);
+        let scene = Scene {
+            positions: &positions,
+            neurons: &neurons,
+            synapses: &synapses,
+            max_weight,
+            options,
+        };
+
+        // Key frame per recorded champion, with fade frames leading into every one after the first
+        let mut frames = Vec::new();
+        for (index, snapshot) in snapshots.iter().enumerate() {
+            let name = index.to_string();
+            if index > 0 {
+                for step in 1..=self.fade_steps {
+                    let t = step as f64 / (self.fade_steps + 1) as f64;
+                    let data = scene.traces(&snapshots[index - 1], snapshot, t);
+                    frames.push(JsonValue::object([("name", format!("{}.{}", name, step).into()), ("data", data)]));
+                }
+            }
+            let data = scene.traces(snapshot, snapshot, 1.0);
+            frames.push(JsonValue::object([("name", name.into()), ("data", data)]));
+        }
+
+        JsonValue::object([
+            ("data", scene.traces(&snapshots[0], &snapshots[0], 1.0)),
+            ("layout", self.layout(&scene)),
+            ("frames", JsonValue::Array(frames)),
+        ])
+    }
+
+    /// Renders the replay as a standalone HTML page or as the plotly figure JSON
+    pub fn render(&self, options: &VisualizeOptions, format: PlotFormat) -> String {
+        let figure = self.to_figure(options);
+        match format {
+            PlotFormat::Json => figure.to_string(),
+            PlotFormat::Html => format!(
+                "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\" />\n<script src=\"{}\"></script>\n</head>\n<body>\n\
+                 <div id=\"replay\" style=\"width:100%;height:100vh;\"></div>\n\
+                 <script>\nPlotly.newPlot(\"replay\", {});\n</script>\n</body>\n</html>\n",
+                PLOTLY_CDN, figure
+            ),
+        }
+    }
+
+    /// Writes the replay, as plotly JSON if the path ends in `.json` and as HTML otherwise
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P, options: &VisualizeOptions) -> io::Result<()> {
+        let format = match path.as_ref().extension() {
+            Some(extension) if extension.eq_ignore_ascii_case("json") => PlotFormat::Json,
+            _ => PlotFormat::Html,
+        };
+        std::fs::write(path, self.render(options, format))
+    }
+
+    fn layout(&self, scene: &Scene) -> JsonValue {
+        let options = scene.options;
+        let (min_x, max_x) = scene
+            .positions
+            .values()
+            .fold((f64::MAX, f64::MIN), |(low, high), &(x, _)| (low.min(x), high.max(x)));
+        // Fixed ranges keep the axes from rescaling as neurons come and go
+        let axis = |range: [f64; 2]| {
+            JsonValue::object([
+                ("range", vec![range[0], range[1]].into()),
+                ("autorange", false.into()),
+                ("visible", (!options.hide_axes).into()),
+                ("showgrid", (!options.hide_axes).into()),
+                ("zeroline", false.into()),
+            ])
+        };
+
+        let frame_ms = self.generation_duration_ms / (self.fade_steps + 1);
+        let animate = |frames: JsonValue, duration: usize| {
+            JsonValue::Array(vec![
+                frames,
+                JsonValue::object([
+                    ("mode", "immediate".into()),
+                    ("fromcurrent", true.into()),
+                    ("frame", JsonValue::object([("duration", duration.into()), ("redraw", true.into())])),
+                    ("transition", JsonValue::object([("duration", 0usize.into())])),
+                ]),
+            ])
+        };
+        let buttons = vec![
+            JsonValue::object([("label", "Play".into()), ("method", "animate".into()), ("args", animate(JsonValue::Null, frame_ms))]),
+            JsonValue::object([
+                ("label", "Pause".into()),
+                ("method", "animate".into()),
+                ("args", animate(JsonValue::Array(vec![JsonValue::Null]), 0)),
+            ]),
+        ];
+        // Slider steps jump to the key frames, whose names match so playback moves the slider along
+        let steps = self
+            .champions
+            .iter()
+            .enumerate()
+            .map(|(index, (generation, _))| {
+                JsonValue::object([
+                    ("label", generation.to_string().into()),
+                    ("method", "animate".into()),
+                    ("args", animate(JsonValue::Array(vec![index.to_string().into()]), 0)),
+                ])
+            })
+            .collect();
+
+        let title = options.title.clone().unwrap_or_else(|| "Champion by generation".to_string());
+        let mut layout = vec![
+            ("title".to_string(), JsonValue::object([("text", title.into())])),
+            ("showlegend".to_string(), false.into()),
+            ("hovermode".to_string(), "closest".into()),
+            ("xaxis".to_string(), axis([min_x - 0.5, max_x + 0.5])),
+            ("yaxis".to_string(), axis([-0.5, 8.5])),
+            (
+                "updatemenus".to_string(),
+                JsonValue::Array(vec![JsonValue::object([
+                    ("type", "buttons".into()),
+                    ("direction", "left".into()),
+                    ("showactive", false.into()),
+                    ("x", 0.0f64.into()),
+                    ("y", 0.0f64.into()),
+                    ("xanchor", "right".into()),
+                    ("yanchor", "top".into()),
+                    ("pad", JsonValue::object([("t", 60usize.into()), ("r", 10usize.into())])),
+                    ("buttons", JsonValue::Array(buttons)),
+                ])]),
+            ),
+            (
+                "sliders".to_string(),
+                JsonValue::Array(vec![JsonValue::object([
+                    ("active", 0usize.into()),
+                    ("x", 0.0f64.into()),
+                    ("y", 0.0f64.into()),
+                    ("len", 1.0f64.into()),
+                    ("pad", JsonValue::object([("t", 50usize.into())])),
+                    ("currentvalue", JsonValue::object([("prefix", "Generation ".into())])),
+                    ("steps", JsonValue::Array(steps)),
+                ])]),
+            ),
+        ];
+        if let Some(width) = options.width {
+            layout.push(("width".to_string(), width.into()));
+        }
+        if let Some(height) = options.height {
+            layout.push(("height".to_string(), height.into()));
+        }
+        JsonValue::Object(layout)
+    }
+}
+
+/// Everything drawn in any frame, in a fixed order so plotly matches traces across frames
+struct Scene<'a> {
+    positions: &'a HashMap<[u8; 2], (f64, f64)>,
+    neurons: &'a [[u8; 2]],
+    synapses: &'a [([u8; 2], [u8; 2])],
+    max_weight: f32,
+    options: &'a VisualizeOptions,
+}
+
+impl Scene<'_> {
+    /// Traces `t` of the way from `from` to `to`: one line per synapse, synapse hover markers, then neurons.
+    /// Opacity is interpolated, color and dash switch halfway.
+    fn traces(&self, from: &Snapshot, to: &Snapshot, t: f64) -> JsonValue {
+        let mut traces = Vec::new();
+        let lerp = |a: f64, b: f64| a + (b - a) * t;
+
+        for key in self.synapses {
+            let (before, after) = (from.synapse(key), to.synapse(key));
+            // Absent synapses keep the look of the state they fade from or into
+            let shown = match (before, after) {
+                (_, SynapseState::Absent) => before,
+                (SynapseState::Absent, _) => after,
+                _ if t < 0.5 => before,
+                _ => after,
+            };
+            let (color, width, dash) = self.synapse_style(shown);
+            let ((x0, y0), (x1, y1)) = (self.positions[&key.0], self.positions[&key.1]);
+            traces.push(JsonValue::object([
+                ("type", "scatter".into()),
+                ("mode", "lines".into()),
+                ("x", vec![x0, x1].into()),
+                ("y", vec![y0, y1].into()),
+                ("opacity", lerp(before.opacity(), after.opacity()).into()),
+                ("line", JsonValue::object([("color", color.into()), ("width", width.into()), ("dash", dash.into())])),
+                ("hoverinfo", "skip".into()),
+            ]));
+        }
+
+        let current = if t < 0.5 { from } else { to };
+        let mut midpoint_x = Vec::new();
+        let mut midpoint_y = Vec::new();
+        let mut midpoint_text = Vec::new();
+        let mut midpoint_hover = Vec::new();
+        for key in self.synapses {
+            let ((x0, y0), (x1, y1)) = (self.positions[&key.0], self.positions[&key.1]);
+            midpoint_x.push((x0 + x1) / 2.0);
+            midpoint_y.push((y0 + y1) / 2.0);
+            let (text, hover) = match current.synapse(key) {
+                SynapseState::Absent => (String::new(), "skip"),
+                SynapseState::Enabled(weight) => (format!("Synapse {:?} -> {:?}<br>Weight: {}<br>enabled", key.0, key.1, weight), "text"),
+                SynapseState::Disabled(weight) => (format!("Synapse {:?} -> {:?}<br>Weight: {}<br>disabled", key.0, key.1, weight), "text"),
+            };
+            midpoint_text.push(text);
+            midpoint_hover.push(hover);
+        }
+        traces.push(JsonValue::object([
+            ("type", "scatter".into()),
+            ("mode", "markers".into()),
+            ("x", midpoint_x.into()),
+            ("y", midpoint_y.into()),
+            ("marker", JsonValue::object([("size", 8usize.into()), ("color", "rgba(0,0,0,0)".into())])),
+            ("hovertext", midpoint_text.into()),
+            ("hoverinfo", midpoint_hover.into()),
+        ]));
+
+        let mut node_x = Vec::new();
+        let mut node_y = Vec::new();
+        let mut colors = Vec::new();
+        let mut opacities = Vec::new();
+        let mut texts = Vec::new();
+        let mut hovers = Vec::new();
+        let mut labels = Vec::new();
+        for id in self.neurons {
+            let (x, y) = self.positions[id];
+            node_x.push(x);
+            node_y.push(y);
+            let (before, after) = (from.neurons.get(id), to.neurons.get(id));
+            let present = |neuron: Option<_>| if neuron.is_some() { 1.0 } else { 0.0 };
+            opacities.push(lerp(present(before), present(after)));
+            let gene_type = after.or(before).map(|neuron| neuron.0);
+            colors.push(match gene_type {
+                Some(GeneType::Input) => "blue",
+                Some(GeneType::Hidden) => "green",
+                _ => "red",
+            });
+            match current.neurons.get(id) {
+                Some((gene_type, bias)) => {
+                    texts.push(format!("ID: {:?}, Type: {:?}<br>Bias: {}", id, gene_type, bias));
+                    hovers.push("text");
+                    labels.push(format!("{}:{}", id[0], id[1]));
+                }
+                None => {
+                    texts.push(String::new());
+                    hovers.push("skip");
+                    labels.push(String::new());
+                }
+            }
+        }
+        let mut neuron_trace = vec![
+            ("type".to_string(), "scatter".into()),
+            ("mode".to_string(), if self.options.node_labels { "markers+text" } else { "markers" }.into()),
+            ("x".to_string(), node_x.into()),
+            ("y".to_string(), node_y.into()),
+            (
+                "marker".to_string(),
+                JsonValue::object([
+                    ("size", 10usize.into()),
+                    ("symbol", "star".into()),
+                    ("color", colors.into()),
+                    ("opacity", opacities.into()),
+                ]),
+            ),
+            ("hovertext".to_string(), texts.into()),
+            ("hoverinfo".to_string(), hovers.into()),
+        ];
+        if self.options.node_labels {
+            neuron_trace.push(("text".to_string(), labels.into()));
+            neuron_trace.push(("textposition".to_string(), "top center".into()));
+        }
+        traces.push(JsonValue::Object(neuron_trace));
+        JsonValue::Array(traces)
+    }
+
+    /// Line color, width and dash, matching the `visualize` tree plot
+    fn synapse_style(&self, state: SynapseState) -> (&'static str, f64, &'static str) {
+        let weight = match state {
+            SynapseState::Enabled(weight) | SynapseState::Disabled(weight) => weight,
+            SynapseState::Absent => 0.0,
+        };
+        let enabled = !matches!(state, SynapseState::Disabled(_));
+        if !self.options.weighted_edges {
+            return if enabled { ("#888", 1.0, "solid") } else { ("#ff0000", 1.0, "dash") };
+        }
+        let width = if self.max_weight > 0.0 { 0.5 + 4.5 * (weight.abs() / self.max_weight) as f64 } else { 1.0 };
+        let color = if weight >= 0.0 { "#2b6cb0" } else { "#c53030" };
+        (color, width, if enabled { "solid" } else { "dash" })
+    }
+}
@@ -0,0 +1,158 @@
+use std::io;
+use std::path::Path;
+
+use crate::visuals::write_plot;
+use crate::{GeneType, GenerationSummary};
+use plotly::{
+    common::{AxisSide, DashType, Fill, Line, Mode},
+    layout::Axis,
+    Layout, Plot, Scatter,
+};
+
+/// Best, median and mean fitness per generation, over a worst-to-best band and a mean ± std dev band
+pub fn fitness_plot(summaries: &[GenerationSummary]) -> Plot {
+    let generations: Vec<usize> = summaries.iter().map(|summary| summary.generation).collect();
+    let series = |value: fn(&GenerationSummary) -> f32| -> Vec<f64> {
+        summaries.iter().map(|summary| value(summary) as f64).collect()
+    };
+    let mut plot = Plot::new();
+
+    let bands = [
+        ("Worst to best", "rgba(66, 133, 244, 0.12)", series(|s| s.worst_fitness), series(|s| s.best_fitness)),
+        (
+            "Mean ± std dev",
+            "rgba(66, 133, 244, 0.3)",
+            series(|s| s.mean_fitness - s.fitness_std_dev),
+            series(|s| s.mean_fitness + s.fitness_std_dev),
+        ),
+    ];
+    for (name, color, lower, upper) in bands {
+        // The upper edge fills down to the lower edge drawn just before it
+        plot.add_trace(
+            Scatter::new(generations.clone(), lower)
+                .mode(Mode::Lines)
+                .line(Line::new().width(0.0))
+                .legend_group(name)
+                .show_legend(false)
+                .name(name),
+        );
+        plot.add_trace(
+            Scatter::new(generations.clone(), upper)
+                .mode(Mode::Lines)
+                .line(Line::new().width(0.0))
+                .fill(Fill::ToNextY)
+                .fill_color(color)
+                .legend_group(name)
+                .name(name),
+        );
+    }
+
+    let lines = [
+        ("Best", "#1a73e8", DashType::Solid, series(|s| s.best_fitness)),
+        ("Median", "#188038", DashType::Dash, series(|s| s.median_fitness)),
+        ("Mean", "#e37400", DashType::Solid, series(|s| s.mean_fitness)),
+    ];
+    for (name, color, dash, values) in lines {
+        plot.add_trace(
+            Scatter::new(generations.clone(), values)
+                .mode(Mode::Lines)
+                .line(Line::new().color(color).width(2.0).dash(dash))
+                .name(name),
+        );
+    }
+
+    plot.set_layout(
+        Layout::new()
+            .title("Fitness per generation")
+            .x_axis(Axis::new().title("Generation"))
+            .y_axis(Axis::new().title("Fitness")),
+    );
+    plot
+}
+
+/// Population mean and champion neuron and synapse counts, with the champion's `get_complexity` on a second axis
+pub fn complexity_plot(summaries: &[GenerationSummary]) -> Plot {
+    let generations: Vec<usize> = summaries.iter().map(|summary| summary.generation).collect();
+    let champion_count = |gene_type: GeneType| -> Vec<f64> {
+        summaries
+            .iter()
+            .map(|summary| summary.champion.genes.iter().filter(|gene| gene.flag[0] == gene_type.as_u8()).count() as f64)
+            .collect()
+    };
+    let mut plot = Plot::new();
+
+    let counts = [
+        ("Mean hidden neurons", "#1a73e8", DashType::Solid, summaries.iter().map(|s| s.mean_hidden_neurons as f64).collect()),
+        ("Mean synapses", "#e37400", DashType::Solid, summaries.iter().map(|s| s.mean_synapses as f64).collect()),
+        ("Champion hidden neurons", "#1a73e8", DashType::Dot, champion_count(GeneType::Hidden)),
+        ("Champion synapses", "#e37400", DashType::Dot, champion_count(GeneType::Synapse)),
+    ];
+    for (name, color, dash, values) in counts {
+        plot.add_trace(
+            Scatter::new(generations.clone(), values)
+                .mode(Mode::Lines)
+                .line(Line::new().color(color).width(2.0).dash(dash))
+                .name(name),
+        );
+    }
+    let complexity = summaries.iter().map(|summary| summary.champion.get_complexity() as f64).collect();
+    plot.add_trace(
+        Scatter::new(generations, complexity)
+            .mode(Mode::Lines)
+            .line(Line::new().color("#5f6368").width(1.5).dash(DashType::Dash))
+            .name("Champion complexity (%)")
+            .y_axis("y2"),
+    );
+
+    plot.set_layout(
+        Layout::new()
+            .title("Complexity per generation")
+            .x_axis(Axis::new().title("Generation"))
+            .y_axis(Axis::new().title("Count"))
+            .y_axis2(
+                Axis::new()
+                    .title("Complexity (% of possible hidden neurons)")
+                    .overlaying("y")
+                    .side(AxisSide::Right),
+            ),
+    );
+    plot
+}
+
+/// Stacked area of species sizes per generation. Species are identified by their index in
+/// `species_sizes`, a species missing from a generation counts as empty.
+pub fn species_plot(summaries: &[GenerationSummary]) -> Plot {
+    let generations: Vec<usize> = summaries.iter().map(|summary| summary.generation).collect();
+    let species_count = summaries.iter().map(|summary| summary.species_sizes.len()).max().unwrap_or(0);
+    let mut plot = Plot::new();
+    for species in 0..species_count {
+        let sizes: Vec<usize> = summaries
+            .iter()
+            .map(|summary| summary.species_sizes.get(species).copied().unwrap_or(0))
+            .collect();
+        plot.add_trace(
+            Scatter::new(generations.clone(), sizes)
+                .mode(Mode::Lines)
+                .line(Line::new().width(0.5))
+                .stack_group("species")
+                .name(format!("Species {}", species)),
+        );
+    }
+
+    plot.set_layout(
+        Layout::new()
+            .title("Species sizes per generation")
+            .x_axis(Axis::new().title("Generation"))
+            .y_axis(Axis::new().title("Genomes")),
+    );
+    plot
+}
+
+/// Writes `fitness.html`, `complexity.html` and `species.html` to a directory, creating it if needed
+pub fn write_run_plots<P: AsRef<Path>>(summaries: &[GenerationSummary], directory: P) -> io::Result<()> {
+    let directory = directory.as_ref();
+    std::fs::create_dir_all(directory)?;
+    write_plot(&fitness_plot(summaries), directory.join("fitness.html"))?;
+    write_plot(&complexity_plot(summaries), directory.join("complexity.html"))?;
+    write_plot(&species_plot(summaries), directory.join("species.html"))
+}
@@ -0,0 +1,183 @@
+use crate::{Genome, Network};
+use rand::prelude::*;
+use rand::rngs::StdRng;
+
+/// Sequence benchmarks that can only be solved by carrying state between network steps.
+///
+/// Bits are presented as -1.0 / 1.0 and every scored timestep has a target of -1.0 or 1.0.
+/// Trials are generated in mirrored pairs: the second trial of each pair sees exactly the same
+/// inputs on every scored timestep but has the opposite targets. A network without recurrent
+/// synapses produces the same output for the same input, so it is right on at most one trial of
+/// each pair and can never score an accuracy above 0.5 on any of these tasks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SequenceTask {
+    /// Shows one bit, waits `delay` blank steps, then asks for the bit back.
+    /// Inputs: [value, recall cue]
+    DelayedRecall { delay: usize },
+    /// Shows `length` bits, then asks for them back in order, one per step.
+    /// Inputs: [value, recall cue]
+    Copy { length: usize },
+    /// Shows `length` bits, then asks whether an odd number of them were 1 (the count modulo 2).
+    /// Inputs: [value, query cue]
+    BitCounting { length: usize },
+    /// Shows a left (-1.0) or right (1.0) cue, walks `corridor` steps, then asks for the turn at the junction.
+    /// Inputs: [cue, corridor, junction]
+    TMaze { corridor: usize },
+}
+
+/// One episode of a sequence task: an input vector per timestep and an optional target per timestep
+#[derive(Debug, Clone, Default)]
+pub struct SequenceTrial {
+    pub inputs: Vec<Vec<f32>>,
+    pub targets: Vec<Option<f32>>,
+}
+
+/// Scores of a network over a set of trials
+#[derive(Debug, Clone)]
+pub struct SequenceScore {
+    /// Fraction of scored timesteps where the output had the sign of the target
+    pub accuracy: f32,
+    /// Mean squared error over scored timesteps
+    pub mse: f32,
+    /// Accuracy for each timestep index, None for timesteps that are never scored
+    pub per_step_accuracy: Vec<Option<f32>>,
+}
+
+impl SequenceTask {
+    /// Number of network inputs the task presents each step
+    pub fn input_count(&self) -> u16 {
+        match self {
+            SequenceTask::TMaze { .. } => 3,
+            _ => 2,
+        }
+    }
+
+    /// Number of network outputs the task reads each step
+    pub fn output_count(&self) -> u16 {
+        1
+    }
+
+    /// Creates an empty network with the inputs and outputs the task expects
+    pub fn new_network(&self) -> Network {
+        Network::from_genome(Genome::new(self.input_count(), self.output_count()))
+    }
+
+    /// Generates `pairs` mirrored trial pairs from a seed
+    pub fn trials(&self, pairs: usize, seed: u64) -> Vec<SequenceTrial> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut trials = Vec::with_capacity(pairs * 2);
+        for _ in 0..pairs {
+            let (trial, mirror) = self.trial_pair(&mut rng);
+            trials.push(trial);
+            trials.push(mirror);
+        }
+        trials
+    }
+
+    /// Runs every trial from a reset network and scores the output on each target timestep
+    pub fn evaluate(&self, network: &mut Network, trials: &[SequenceTrial]) -> SequenceScore {
+        assert_eq!(network.input_count(), self.input_count() as usize, "{:?} needs {} inputs", self, self.input_count());
+        assert_eq!(network.output_count(), self.output_count() as usize, "{:?} needs {} outputs", self, self.output_count());
+
+        let steps = trials.iter().map(|trial| trial.inputs.len()).max().unwrap_or(0);
+        let mut correct_per_step = vec![0usize; steps];
+        let mut scored_per_step = vec![0usize; steps];
+        let mut squared_error = 0.0;
+
+        for trial in trials {
+            network.reset();
+            for (step, (inputs, target)) in trial.inputs.iter().zip(&trial.targets).enumerate() {
+                let output = network.activate(inputs)[0];
+                if let Some(target) = target {
+                    scored_per_step[step] += 1;
+                    if output.signum() == target.signum() && output != 0.0 {
+                        correct_per_step[step] += 1;
+                    }
+                    squared_error += (output - target).powi(2);
+                }
+            }
+        }
+
+        let scored: usize = scored_per_step.iter().sum();
+        let correct: usize = correct_per_step.iter().sum();
+        SequenceScore {
+            accuracy: if scored == 0 { 0.0 } else { correct as f32 / scored as f32 },
+            mse: if scored == 0 { 0.0 } else { squared_error / scored as f32 },
+            per_step_accuracy: correct_per_step
+                .iter()
+                .zip(&scored_per_step)
+                .map(|(&correct, &scored)| (scored > 0).then(|| correct as f32 / scored as f32))
+                .collect(),
+        }
+    }
+
+    /// Builds a trial and its mirror, which has opposite targets and identical inputs on scored steps
+    fn trial_pair(&self, rng: &mut StdRng) -> (SequenceTrial, SequenceTrial) {
+        let bit = |rng: &mut StdRng| if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+        match *self {
+            SequenceTask::DelayedRecall { delay } => {
+                let value = bit(rng);
+                let build = |value: f32| {
+                    let mut trial = SequenceTrial::default();
+                    trial.push(vec![value, 0.0], None);
+                    for _ in 0..delay {
+                        trial.push(vec![0.0, 0.0], None);
+                    }
+                    trial.push(vec![0.0, 1.0], Some(value));
+                    trial
+                };
+                (build(value), build(-value))
+            }
+            SequenceTask::Copy { length } => {
+                let bits: Vec<f32> = (0..length).map(|_| bit(rng)).collect();
+                let build = |sign: f32| {
+                    let mut trial = SequenceTrial::default();
+                    for &value in &bits {
+                        trial.push(vec![sign * value, 0.0], None);
+                    }
+                    for &value in &bits {
+                        trial.push(vec![0.0, 1.0], Some(sign * value));
+                    }
+                    trial
+                };
+                (build(1.0), build(-1.0))
+            }
+            SequenceTask::BitCounting { length } => {
+                let mut bits: Vec<f32> = (0..length.max(1)).map(|_| bit(rng)).collect();
+                let build = |bits: &[f32]| {
+                    let ones = bits.iter().filter(|&&value| value > 0.0).count();
+                    let mut trial = SequenceTrial::default();
+                    for &value in bits {
+                        trial.push(vec![value, 0.0], None);
+                    }
+                    trial.push(vec![0.0, 1.0], Some(if ones % 2 == 1 { 1.0 } else { -1.0 }));
+                    trial
+                };
+                let trial = build(&bits);
+                // Flipping one bit flips the parity
+                bits[0] = -bits[0];
+                (trial, build(&bits))
+            }
+            SequenceTask::TMaze { corridor } => {
+                let cue = bit(rng);
+                let build = |cue: f32| {
+                    let mut trial = SequenceTrial::default();
+                    trial.push(vec![cue, 0.0, 0.0], None);
+                    for _ in 0..corridor {
+                        trial.push(vec![0.0, 1.0, 0.0], None);
+                    }
+                    trial.push(vec![0.0, 0.0, 1.0], Some(cue));
+                    trial
+                };
+                (build(cue), build(-cue))
+            }
+        }
+    }
+}
+
+impl SequenceTrial {
+    fn push(&mut self, inputs: Vec<f32>, target: Option<f32>) {
+        self.inputs.push(inputs);
+        self.targets.push(target);
+    }
+}
@@ -0,0 +1,508 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+
+use crate::{convert_f32_to_id, convert_id_to_f32, get_tree_parent, Gene, GeneType, Genome, Lineage, MutationConfig, SynapseStatus};
+
+/// First bytes of every serialized genome
+pub const GENOME_MAGIC: [u8; 4] = *b"ASTG";
+/// Binary format version written by `Genome::to_bytes`
+pub const GENOME_FORMAT_VERSION: u16 = 1;
+
+/// Prefix of every genome code
+pub const GENOME_CODE_PREFIX: &str = "aster:";
+
+const HEADER_LEN: usize = 4 + 2 + 2 + 2 + 4 + 4 + 4;
+const GENE_LEN: usize = 12;
+
+#[derive(Debug)]
+pub enum GenomeError {
+    Io(std::io::Error),
+    BadMagic([u8; 4]),
+    UnsupportedVersion(u16),
+    Truncated { needed: usize, found: usize },
+    TrailingBytes(usize),
+    UnknownGeneType { gene: usize, flag: u8 },
+    UnknownSynapseStatus { gene: usize, flag: u8 },
+    NotFinite { gene: usize },
+    InvalidNeuronId { gene: usize, id: [u8; 2] },
+    DuplicateNeuron([u8; 2]),
+    DuplicateSynapse { from: [u8; 2], to: [u8; 2] },
+    DanglingSynapse { from: [u8; 2], to: [u8; 2] },
+    MissingTreeParent { id: [u8; 2], parent: [u8; 2] },
+    ChildCountMismatch { id: [u8; 2], count: u8, found: usize },
+    CountMismatch { inputs: usize, outputs: usize, expected_inputs: usize, expected_outputs: usize },
+    MissingCodePrefix,
+    InvalidBase64(base64::DecodeError),
+    ChecksumMismatch { expected: u32, found: u32 },
+    Syntax { line: usize, column: usize, message: String },
+}
+
+impl fmt::Display for GenomeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GenomeError::Io(err) => write!(f, "genome file I/O failed: {}", err),
+            GenomeError::BadMagic(found) => write!(f, "not a genome file, header starts with {:?}", found),
+            GenomeError::UnsupportedVersion(version) => write!(
+                f,
+                "genome format version {} is not supported, expected {}",
+                version, GENOME_FORMAT_VERSION
+            ),
+            GenomeError::Truncated { needed, found } => {
+                write!(f, "genome data is truncated, needed {} bytes, found {}", needed, found)
+            }
+            GenomeError::TrailingBytes(count) => write!(f, "{} unexpected bytes after the last gene", count),
+            GenomeError::UnknownGeneType { gene, flag } => write!(f, "gene {} has unknown type {}", gene, flag),
+            GenomeError::UnknownSynapseStatus { gene, flag } => {
+                write!(f, "synapse gene {} has unknown status {}", gene, flag)
+            }
+            GenomeError::NotFinite { gene } => write!(f, "gene {} has a value that is not finite", gene),
+            GenomeError::InvalidNeuronId { gene, id } => write!(f, "gene {} has invalid neuron id {:?}", gene, id),
+            GenomeError::DuplicateNeuron(id) => write!(f, "neuron {:?} appears more than once", id),
+            GenomeError::DuplicateSynapse { from, to } => {
+                write!(f, "synapse {:?} -> {:?} appears more than once", from, to)
+            }
+            GenomeError::DanglingSynapse { from, to } => {
+                write!(f, "synapse {:?} -> {:?} does not connect two existing neurons", from, to)
+            }
+            GenomeError::MissingTreeParent { id, parent } => {
+                write!(f, "hidden neuron {:?} has no input or hidden parent {:?}", id, parent)
+            }
+            GenomeError::ChildCountMismatch { id, count, found } => {
+                write!(f, "neuron {:?} records {} children but {} are present", id, count, found)
+            }
+            GenomeError::CountMismatch { inputs, outputs, expected_inputs, expected_outputs } => write!(
+                f,
+                "genome has {} inputs and {} outputs, header says {} and {}",
+                inputs, outputs, expected_inputs, expected_outputs
+            ),
+            GenomeError::MissingCodePrefix => write!(f, "genome code must start with {:?}", GENOME_CODE_PREFIX),
+            GenomeError::InvalidBase64(err) => write!(f, "genome code is not valid base64: {}", err),
+            GenomeError::ChecksumMismatch { expected, found } => write!(
+                f,
+                "genome code checksum is {:08x} but the data hashes to {:08x}, was it copied completely?",
+                expected, found
+            ),
+            GenomeError::Syntax { line, column, message } => write!(f, "line {}, column {}: {}", line, column, message),
+        }
+    }
+}
+
+impl std::error::Error for GenomeError {}
+
+impl Genome {
+    /// Number of input neurons
+    pub fn input_count(&self) -> usize {
+        self.genes.iter().filter(|gene| GeneType::from_u8(gene.flag[0]) == Some(GeneType::Input)).count()
+    }
+
+    /// Number of output neurons
+    pub fn output_count(&self) -> usize {
+        self.genes.iter().filter(|gene| GeneType::from_u8(gene.flag[0]) == Some(GeneType::Output)).count()
+    }
+
+    /// Checks that the genes describe a network that can be built.
+    /// Inputs must be `[0, 0]..[n - 1, 0]`, outputs `[255, 0]` downwards, hidden neurons must sit below
+    /// an existing input or hidden parent in their tree, every neuron's child count must match the children
+    /// present, and every synapse must run from an existing neuron to a non-input neuron.
+    pub fn validate(&self) -> Result<(), GenomeError> {
+        let mut neurons = HashSet::new();
+        let mut inputs = HashSet::new();
+        for (index, gene) in self.genes.iter().enumerate() {
+            let gene_type = GeneType::from_u8(gene.flag[0])
+                .ok_or(GenomeError::UnknownGeneType { gene: index, flag: gene.flag[0] })?;
+            if !gene.local_data.is_finite() || !gene.extern_data.is_finite() {
+                return Err(GenomeError::NotFinite { gene: index });
+            }
+            let valid_id = match gene_type {
+                GeneType::Synapse => {
+                    if SynapseStatus::from_u8(gene.flag[1]).is_none() {
+                        return Err(GenomeError::UnknownSynapseStatus { gene: index, flag: gene.flag[1] });
+                    }
+                    convert_id_to_f32(convert_f32_to_id(gene.local_data)) == gene.local_data
+                }
+                GeneType::Hidden => gene.id[1] > 0,
+                GeneType::Input | GeneType::Output => gene.id[1] == 0,
+            };
+            if !valid_id {
+                return Err(GenomeError::InvalidNeuronId { gene: index, id: gene.id });
+            }
+            if gene_type != GeneType::Synapse && !neurons.insert(gene.id) {
+                return Err(GenomeError::DuplicateNeuron(gene.id));
+            }
+            if gene_type == GeneType::Input {
+                inputs.insert(gene.id);
+            }
+        }
+
+        let (input_count, output_count) = (self.input_count(), self.output_count());
+        for (index, gene) in self.genes.iter().enumerate() {
+            let in_range = match GeneType::from_u8(gene.flag[0]) {
+                Some(GeneType::Input) => (gene.id[0] as usize) < input_count,
+                Some(GeneType::Output) => 255 - (gene.id[0] as usize) < output_count,
+                _ => true,
+            };
+            if !in_range {
+                return Err(GenomeError::InvalidNeuronId { gene: index, id: gene.id });
+            }
+        }
+
+        let mut children: HashMap<[u8; 2], usize> = HashMap::new();
+        for gene in self.genes.iter().filter(|gene| gene.flag[0] == GeneType::Hidden.as_u8()) {
+            let parent = get_tree_parent(gene.id).expect("hidden ids were checked above");
+            let parent_type = self
+                .genes
+                .iter()
+                .find(|candidate| candidate.id == parent && candidate.flag[0] != GeneType::Synapse.as_u8())
+                .and_then(|candidate| GeneType::from_u8(candidate.flag[0]));
+            if !matches!(parent_type, Some(GeneType::Input | GeneType::Hidden)) {
+                return Err(GenomeError::MissingTreeParent { id: gene.id, parent });
+            }
+            *children.entry(parent).or_default() += 1;
+        }
+        for gene in self.genes.iter().filter(|gene| gene.flag[0] != GeneType::Synapse.as_u8()) {
+            let found = children.get(&gene.id).copied().unwrap_or(0);
+            if gene.flag[1] as usize != found {
+                return Err(GenomeError::ChildCountMismatch { id: gene.id, count: gene.flag[1], found });
+            }
+        }
+
+        let mut synapses = HashSet::new();
+        for gene in self.genes.iter().filter(|gene| gene.flag[0] == GeneType::Synapse.as_u8()) {
+            let (from, to) = (gene.id, convert_f32_to_id(gene.local_data));
+            if !neurons.contains(&from) || !neurons.contains(&to) || inputs.contains(&to) {
+                return Err(GenomeError::DanglingSynapse { from, to });
+            }
+            if !synapses.insert((from, to)) {
+                return Err(GenomeError::DuplicateSynapse { from, to });
+            }
+        }
+        Ok(())
+    }
+
+    /// Serializes the genome to a compact little-endian binary format.
+    ///
+    /// Layout: magic `ASTG`, version (u16), input count (u16), output count (u16), connection bias (u32),
+    /// inclusion probability (f32), gene count (u32), then one 12 byte record per gene:
+    /// id (2 x u8), flag (2 x u8), local data (f32), extern data (f32).
+    /// The lineage is not stored, `from_bytes` and `load` start the genome at `Lineage::root`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.genes.len() * GENE_LEN);
+        bytes.extend_from_slice(&GENOME_MAGIC);
+        bytes.extend_from_slice(&GENOME_FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(self.input_count() as u16).to_le_bytes());
+        bytes.extend_from_slice(&(self.output_count() as u16).to_le_bytes());
+        bytes.extend_from_slice(&(self.config.connection_bias as u32).to_le_bytes());
+        bytes.extend_from_slice(&self.config.inclusion_probability.to_le_bytes());
+        bytes.extend_from_slice(&(self.genes.len() as u32).to_le_bytes());
+        for gene in &self.genes {
+            bytes.extend_from_slice(&gene.id);
+            bytes.extend_from_slice(&gene.flag);
+            bytes.extend_from_slice(&gene.local_data.to_le_bytes());
+            bytes.extend_from_slice(&gene.extern_data.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Reads a genome written by `to_bytes` and validates it
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, GenomeError> {
+        if bytes.len() < HEADER_LEN {
+            if bytes.len() >= 4 && bytes[..4] != GENOME_MAGIC {
+                return Err(GenomeError::BadMagic(bytes[..4].try_into().unwrap()));
+            }
+            return Err(GenomeError::Truncated { needed: HEADER_LEN, found: bytes.len() });
+        }
+        let mut reader = ByteReader { bytes, position: 0 };
+        let magic = reader.take::<4>();
+        if magic != GENOME_MAGIC {
+            return Err(GenomeError::BadMagic(magic));
+        }
+        let version = u16::from_le_bytes(reader.take());
+        if version != GENOME_FORMAT_VERSION {
+            return Err(GenomeError::UnsupportedVersion(version));
+        }
+        let expected_inputs = u16::from_le_bytes(reader.take()) as usize;
+        let expected_outputs = u16::from_le_bytes(reader.take()) as usize;
+        let config = MutationConfig {
+            connection_bias: u32::from_le_bytes(reader.take()) as usize,
+            inclusion_probability: f32::from_le_bytes(reader.take()),
+        };
+        let gene_count = u32::from_le_bytes(reader.take()) as usize;
+
+        let needed = gene_count
+            .checked_mul(GENE_LEN)
+            .and_then(|len| len.checked_add(HEADER_LEN))
+            .unwrap_or(usize::MAX);
+        if bytes.len() < needed {
+            return Err(GenomeError::Truncated { needed, found: bytes.len() });
+        }
+        if bytes.len() > needed {
+            return Err(GenomeError::TrailingBytes(bytes.len() - needed));
+        }
+        let genes = (0..gene_count)
+            .map(|_| Gene {
+                id: reader.take(),
+                flag: reader.take(),
+                local_data: f32::from_le_bytes(reader.take()),
+                extern_data: f32::from_le_bytes(reader.take()),
+            })
+            .collect();
+
+        let genome = Genome { genes, config, lineage: Lineage::root() };
+        genome.validate()?;
+        let (inputs, outputs) = (genome.input_count(), genome.output_count());
+        if inputs != expected_inputs || outputs != expected_outputs {
+            return Err(GenomeError::CountMismatch { inputs, outputs, expected_inputs, expected_outputs });
+        }
+        Ok(genome)
+    }
+
+    /// Writes the genome to a file in the `to_bytes` format
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), GenomeError> {
+        std::fs::write(path, self.to_bytes()).map_err(GenomeError::Io)
+    }
+
+    /// Reads a genome file written by `save`
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, GenomeError> {
+        Self::from_bytes(&std::fs::read(path).map_err(GenomeError::Io)?)
+    }
+}
+
+impl Genome {
+    /// Encodes the genome as a single line of text for copy-pasting: `aster:` followed by URL-safe
+    /// base64 of the `to_bytes` data with its CRC-32 appended. Like `to_bytes` it leaves out the lineage.
+    pub fn to_code(&self) -> String {
+        let mut bytes = self.to_bytes();
+        bytes.extend_from_slice(&crc32(&bytes).to_le_bytes());
+        format!("{}{}", GENOME_CODE_PREFIX, URL_SAFE_NO_PAD.encode(bytes))
+    }
+
+    /// Decodes a code written by `to_code`. Whitespace is ignored, so codes wrapped across lines still load.
+    pub fn from_code(code: &str) -> Result<Self, GenomeError> {
+        let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+        let payload = code.strip_prefix(GENOME_CODE_PREFIX).ok_or(GenomeError::MissingCodePrefix)?;
+        let bytes = URL_SAFE_NO_PAD.decode(payload).map_err(GenomeError::InvalidBase64)?;
+        if bytes.len() < 4 {
+            return Err(GenomeError::Truncated { needed: HEADER_LEN + 4, found: bytes.len() });
+        }
+        let (data, checksum) = bytes.split_at(bytes.len() - 4);
+        let expected = u32::from_le_bytes(checksum.try_into().unwrap());
+        let found = crc32(data);
+        if expected != found {
+            return Err(GenomeError::ChecksumMismatch { expected, found });
+        }
+        Self::from_bytes(data)
+    }
+}
+
+impl Genome {
+    /// Writes the genome in the line-oriented text format read by `Genome::from_str`, one gene per line
+    /// in gene order:
+    ///
+    /// ```text
+    /// config connection_bias=4 inclusion_probability=0.5
+    /// input 0:0 children=1 bias=0 activation=0
+    /// hidden 0:1 children=0 bias=0.25 activation=0
+    /// synapse 0:1 -> 255:0 weight=-1.5 enabled
+    /// output 255:0 children=0 bias=0 activation=0
+    /// ```
+    ///
+    /// Ids are `seed:position`. Numbers are printed with the shortest exact representation, so
+    /// parsing the text gives back identical genes. Genes with an unknown type or synapse status have
+    /// no text form and are reported the way `validate` reports them. The text holds genes and config only,
+    /// a parsed genome gets a fresh `Lineage::root`.
+    pub fn to_text(&self) -> Result<String, GenomeError> {
+        let mut text = format!(
+            "config connection_bias={} inclusion_probability={}\n",
+            self.config.connection_bias, self.config.inclusion_probability
+        );
+        for (index, gene) in self.genes.iter().enumerate() {
+            let kind = match GeneType::from_u8(gene.flag[0]) {
+                Some(GeneType::Synapse) => {
+                    let status = match SynapseStatus::from_u8(gene.flag[1]) {
+                        Some(SynapseStatus::Enabled) => "enabled",
+                        Some(SynapseStatus::Disabled) => "disabled",
+                        None => return Err(GenomeError::UnknownSynapseStatus { gene: index, flag: gene.flag[1] }),
+                    };
+                    let to = convert_f32_to_id(gene.local_data);
+                    text += &format!(
+                        "synapse {}:{} -> {}:{} weight={} {}\n",
+                        gene.id[0], gene.id[1], to[0], to[1], gene.extern_data, status
+                    );
+                    continue;
+                }
+                Some(GeneType::Input) => "input",
+                Some(GeneType::Hidden) => "hidden",
+                Some(GeneType::Output) => "output",
+                None => return Err(GenomeError::UnknownGeneType { gene: index, flag: gene.flag[0] }),
+            };
+            text += &format!(
+                "{} {}:{} children={} bias={} activation={}\n",
+                kind, gene.id[0], gene.id[1], gene.flag[1], gene.local_data, gene.extern_data
+            );
+        }
+        Ok(text)
+    }
+}
+
+impl FromStr for Genome {
+    type Err = GenomeError;
+
+    /// Parses the `to_text` format and validates the result. Blank lines and text after `#` are ignored,
+    /// `config` may be left out, and omitted neuron fields and synapse status take their defaults.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut config = None;
+        let mut genes = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("");
+            let mut tokens = line.split_whitespace().map(|token| {
+                let start = token.as_ptr() as usize - line.as_ptr() as usize;
+                Token { text: token, line: index + 1, column: line[..start].chars().count() + 1 }
+            });
+            let Some(keyword) = tokens.next() else {
+                continue;
+            };
+
+            let gene_type = match keyword.text {
+                "config" => {
+                    if config.is_some() {
+                        return Err(keyword.error("config given more than once"));
+                    }
+                    let mut parsed = MutationConfig::default();
+                    for token in tokens {
+                        match token.field()? {
+                            ("connection_bias", value) => parsed.connection_bias = value.parse_as(&token)?,
+                            ("inclusion_probability", value) => parsed.inclusion_probability = value.parse_as(&token)?,
+                            (key, _) => return Err(token.error(&format!("unknown config field {:?}", key))),
+                        }
+                    }
+                    config = Some(parsed);
+                    continue;
+                }
+                "synapse" => {
+                    let from = tokens.next().ok_or_else(|| keyword.error("synapse needs a source id"))?.id()?;
+                    match tokens.next() {
+                        Some(arrow) if arrow.text == "->" => {}
+                        Some(token) => return Err(token.error("expected \"->\"")),
+                        None => return Err(keyword.error("synapse needs \"->\" and a destination id")),
+                    }
+                    let to = tokens.next().ok_or_else(|| keyword.error("synapse needs a destination id"))?.id()?;
+                    let mut gene = Gene {
+                        id: from,
+                        flag: [GeneType::Synapse.as_u8(), SynapseStatus::Enabled as u8],
+                        local_data: convert_id_to_f32(to),
+                        extern_data: 0.0,
+                    };
+                    for token in tokens {
+                        match token.text {
+                            "enabled" => gene.flag[1] = SynapseStatus::Enabled as u8,
+                            "disabled" => gene.flag[1] = SynapseStatus::Disabled as u8,
+                            _ => match token.field()? {
+                                ("weight", value) => gene.extern_data = value.parse_as(&token)?,
+                                (key, _) => return Err(token.error(&format!("unknown synapse field {:?}", key))),
+                            },
+                        }
+                    }
+                    genes.push(gene);
+                    continue;
+                }
+                "input" => GeneType::Input,
+                "hidden" => GeneType::Hidden,
+                "output" => GeneType::Output,
+                other => return Err(keyword.error(&format!("unknown gene kind {:?}", other))),
+            };
+
+            let id = tokens.next().ok_or_else(|| keyword.error("neuron needs an id"))?.id()?;
+            let mut gene = Gene { id, flag: [gene_type.as_u8(), 0], local_data: 0.0, extern_data: 0.0 };
+            for token in tokens {
+                match token.field()? {
+                    ("children", value) => gene.flag[1] = value.parse_as(&token)?,
+                    ("bias", value) => gene.local_data = value.parse_as(&token)?,
+                    ("activation", value) => gene.extern_data = value.parse_as(&token)?,
+                    (key, _) => return Err(token.error(&format!("unknown neuron field {:?}", key))),
+                }
+            }
+            genes.push(gene);
+        }
+
+        let genome = Genome { genes, config: config.unwrap_or_default(), lineage: Lineage::root() };
+        genome.validate()?;
+        Ok(genome)
+    }
+}
+
+/// Whitespace separated word of the text format with its 1-based position
+struct Token<'a> {
+    text: &'a str,
+    line: usize,
+    column: usize,
+}
+
+/// Value half of a `key=value` token
+struct FieldValue<'a> {
+    text: &'a str,
+    column: usize,
+}
+
+impl<'a> Token<'a> {
+    fn error(&self, message: &str) -> GenomeError {
+        GenomeError::Syntax { line: self.line, column: self.column, message: message.to_string() }
+    }
+
+    fn id(&self) -> Result<[u8; 2], GenomeError> {
+        let parse = |part: &str| part.parse::<u8>().ok();
+        match self.text.split_once(':') {
+            Some((seed, position)) => parse(seed).zip(parse(position)).map(|(seed, position)| [seed, position]),
+            None => None,
+        }
+        .ok_or_else(|| self.error(&format!("expected an id like 0:1, found {:?}", self.text)))
+    }
+
+    fn field(&self) -> Result<(&'a str, FieldValue<'a>), GenomeError> {
+        let (key, value) = self
+            .text
+            .split_once('=')
+            .ok_or_else(|| self.error(&format!("expected key=value, found {:?}", self.text)))?;
+        Ok((key, FieldValue { text: value, column: self.column + key.chars().count() + 1 }))
+    }
+}
+
+impl FieldValue<'_> {
+    fn parse_as<T: FromStr>(&self, token: &Token) -> Result<T, GenomeError> {
+        self.text.parse().map_err(|_| GenomeError::Syntax {
+            line: token.line,
+            column: self.column,
+            message: format!("invalid value {:?}", self.text),
+        })
+    }
+}
+
+/// CRC-32 (IEEE), bitwise since genome codes are short
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+/// Cursor over a buffer whose length has already been checked
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl ByteReader<'_> {
+    fn take<const N: usize>(&mut self) -> [u8; N] {
+        let chunk = self.bytes[self.position..self.position + N].try_into().unwrap();
+        self.position += N;
+        chunk
+    }
+}
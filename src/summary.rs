@@ -0,0 +1,61 @@
+use crate::{GeneType, Genome};
+
+/// Population statistics of one generation, the unit of run logs and run plots
+#[derive(Debug, Clone)]
+pub struct GenerationSummary {
+    pub generation: usize,
+    pub population_size: usize,
+    pub best_fitness: f32,
+    pub mean_fitness: f32,
+    pub median_fitness: f32,
+    pub worst_fitness: f32,
+    pub fitness_std_dev: f32,
+    pub mean_hidden_neurons: f32,
+    pub mean_synapses: f32,
+    /// Member count of each species, empty when the run does not speciate
+    pub species_sizes: Vec<usize>,
+    /// Fittest genome of the generation
+    pub champion: Genome,
+}
+
+impl GenerationSummary {
+    /// Summarizes a scored population, `fitness[i]` belonging to `population[i]`
+    pub fn new(generation: usize, population: &[Genome], fitness: &[f32]) -> Self {
+        assert!(!population.is_empty(), "Cannot summarize an empty population");
+        assert_eq!(population.len(), fitness.len(), "Expected one fitness per genome");
+
+        let mut sorted = fitness.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let count = sorted.len();
+        let median_fitness = if count % 2 == 1 {
+            sorted[count / 2]
+        } else {
+            (sorted[count / 2 - 1] + sorted[count / 2]) / 2.0
+        };
+        let mean_fitness = fitness.iter().sum::<f32>() / count as f32;
+        let variance = fitness.iter().map(|value| (value - mean_fitness).powi(2)).sum::<f32>() / count as f32;
+        let champion = (0..count).max_by(|&a, &b| fitness[a].total_cmp(&fitness[b])).unwrap();
+
+        let mean_count = |gene_type: GeneType| {
+            population
+                .iter()
+                .map(|genome| genome.genes.iter().filter(|gene| gene.flag[0] == gene_type.as_u8()).count())
+                .sum::<usize>() as f32
+                / population.len() as f32
+        };
+
+        GenerationSummary {
+            generation,
+            population_size: count,
+            best_fitness: sorted[count - 1],
+            mean_fitness,
+            median_fitness,
+            worst_fitness: sorted[0],
+            fitness_std_dev: variance.sqrt(),
+            mean_hidden_neurons: mean_count(GeneType::Hidden),
+            mean_synapses: mean_count(GeneType::Synapse),
+            species_sizes: Vec::new(),
+            champion: population[champion].clone(),
+        }
+    }
+}
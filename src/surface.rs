@@ -0,0 +1,130 @@
+use crate::{Dataset, Network};
+use plotly::{
+    common::{ColorBar, ColorScale, ColorScalePalette, Line, Marker, Mode},
+    contour::{Coloring, Contours},
+    layout::Axis,
+    Contour, HeatMap, Layout, Plot, Scatter,
+};
+
+/// Categorical colors for dataset classes
+const CLASS_COLORS: [&str; 8] = ["#1a73e8", "#d93025", "#188038", "#f9ab00", "#9334e6", "#12b5cb", "#e8710a", "#5f6368"];
+
+impl Network {
+    /// Heatmap of one output of a 2-input network over a grid of `resolution` x `resolution` inputs,
+    /// with the output = 0 contour drawn as the decision boundary.
+    /// Every grid point is evaluated from a reset state, like `Dataset::predict`.
+    pub fn plot_response_surface(
+        &self,
+        output_idx: usize,
+        x_range: (f32, f32),
+        y_range: (f32, f32),
+        resolution: usize,
+    ) -> Plot {
+        assert_eq!(self.input_count(), 2, "Response surfaces need a network with 2 inputs");
+        assert!(output_idx < self.output_count(), "Network has no output {}", output_idx);
+        assert!(resolution >= 2, "Resolution must be at least 2");
+
+        let axis = |(start, end): (f32, f32)| -> Vec<f32> {
+            (0..resolution).map(|i| start + (end - start) * i as f32 / (resolution - 1) as f32).collect()
+        };
+        let (xs, ys) = (axis(x_range), axis(y_range));
+        let grid: Vec<[f32; 2]> = ys.iter().flat_map(|&y| xs.iter().map(move |&x| [x, y])).collect();
+        let z: Vec<Vec<f64>> = self
+            .activate_batch(&grid)
+            .chunks(resolution)
+            .map(|row| row.iter().map(|outputs| outputs[output_idx] as f64).collect())
+            .collect();
+        let xs: Vec<f64> = xs.iter().map(|&x| x as f64).collect();
+        let ys: Vec<f64> = ys.iter().map(|&y| y as f64).collect();
+
+        let mut plot = Plot::new();
+        plot.add_trace(
+            HeatMap::new(xs.clone(), ys.clone(), z.clone())
+                .name(format!("Output {}", output_idx))
+                .color_scale(ColorScale::Palette(ColorScalePalette::RdBu))
+                .zmin(-1.0)
+                .zmax(1.0)
+                .color_bar(ColorBar::new().title(format!("Output {}", output_idx).as_str())),
+        );
+        plot.add_trace(
+            Contour::new(xs, ys, z)
+                .name("Output = 0")
+                .contours(Contours::new().coloring(Coloring::Lines).start(0.0).end(0.0))
+                .line(Line::new().color("black").width(2.0))
+                .show_scale(false),
+        );
+        plot.set_layout(
+            Layout::new()
+                .title(format!("Response surface of output {}", output_idx).as_str())
+                .x_axis(Axis::new().title("Input 0"))
+                .y_axis(Axis::new().title("Input 1")),
+        );
+        plot
+    }
+
+    /// `plot_response_surface` with the dataset's rows drawn on top, one color per class for
+    /// classification data and colored by target column `output_idx` over its range for regression data
+    pub fn plot_response_surface_with_points(
+        &self,
+        output_idx: usize,
+        x_range: (f32, f32),
+        y_range: (f32, f32),
+        resolution: usize,
+        dataset: &Dataset,
+    ) -> Plot {
+        assert_eq!(dataset.input_count(), 2, "Dataset must have 2 features to overlay");
+        let mut plot = self.plot_response_surface(output_idx, x_range, y_range, resolution);
+        let coordinates = |rows: &[usize]| -> (Vec<f64>, Vec<f64>) {
+            rows.iter()
+                .map(|&row| (dataset.features[row][0] as f64, dataset.features[row][1] as f64))
+                .unzip()
+        };
+
+        if dataset.class_names.is_empty() {
+            assert!(
+                dataset.is_empty() || output_idx < dataset.output_count(),
+                "Cannot color points by target column {}, the dataset has {} target columns",
+                output_idx,
+                dataset.output_count()
+            );
+            let rows: Vec<usize> = (0..dataset.len()).collect();
+            let (x, y) = coordinates(&rows);
+            let targets: Vec<f64> = dataset.targets.iter().map(|target| target[output_idx] as f64).collect();
+            let low = targets.iter().copied().fold(f64::INFINITY, f64::min);
+            let high = targets.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            // A constant target still needs a non-empty range
+            let (low, high) = if low < high { (low, high) } else if low.is_finite() { (low - 1.0, low + 1.0) } else { (-1.0, 1.0) };
+            plot.add_trace(
+                Scatter::new(x, y).mode(Mode::Markers).name("Targets").marker(
+                    Marker::new()
+                        .size(8)
+                        .color_array(targets)
+                        .color_scale(ColorScale::Palette(ColorScalePalette::RdBu))
+                        .cmin(low)
+                        .cmax(high)
+                        .line(Line::new().color("black").width(1.0)),
+                ),
+            );
+            return plot;
+        }
+
+        for (class, name) in dataset.class_names.iter().enumerate() {
+            let rows: Vec<usize> = (0..dataset.len())
+                .filter(|&row| {
+                    let target = &dataset.targets[row];
+                    (0..target.len()).max_by(|&a, &b| target[a].total_cmp(&target[b])) == Some(class)
+                })
+                .collect();
+            let (x, y) = coordinates(&rows);
+            plot.add_trace(
+                Scatter::new(x, y).mode(Mode::Markers).name(name).marker(
+                    Marker::new()
+                        .size(8)
+                        .color(CLASS_COLORS[class % CLASS_COLORS.len()])
+                        .line(Line::new().color("white").width(1.0)),
+                ),
+            );
+        }
+        plot
+    }
+}
@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+
+use crate::{convert_f32_to_id, get_inorder_position, get_neuron_height, get_tree_parent, Gene, GeneType, Genome, SynapseStatus};
+
+/// Characters used to draw tree branches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TreeCharset {
+    /// Box-drawing characters and arrows
+    #[default]
+    Unicode,
+    /// Plain ASCII for terminals and logs without Unicode support
+    Ascii,
+}
+
+struct Branches {
+    tee: &'static str,
+    corner: &'static str,
+    pipe: &'static str,
+    blank: &'static str,
+    link: &'static str,
+    incoming: &'static str,
+}
+
+impl TreeCharset {
+    fn branches(&self) -> Branches {
+        match self {
+            TreeCharset::Unicode => Branches {
+                tee: "├─",
+                corner: "└─",
+                pipe: "│  ",
+                blank: "   ",
+                link: "⇢",
+                incoming: "←",
+            },
+            TreeCharset::Ascii => Branches {
+                tee: "|-",
+                corner: "`-",
+                pipe: "|  ",
+                blank: "   ",
+                link: "->",
+                incoming: "<-",
+            },
+        }
+    }
+}
+
+/// Synapse as drawn: destination, weight, enabled
+type Link = ([u8; 2], f32, bool);
+
+struct TreeRenderer<'a> {
+    neurons: HashMap<[u8; 2], &'a Gene>,
+    outgoing: HashMap<[u8; 2], Vec<Link>>,
+    branches: Branches,
+    text: String,
+}
+
+// Terminal rendering of the neuron trees
+impl Genome {
+    /// Prints the neuron trees, see `render_tree`
+    pub fn display_tree(&self) {
+        print!("{}", self.render_tree(TreeCharset::Unicode));
+    }
+
+    /// Draws each input's binary tree of hidden neurons, left child before right child.
+    /// A tree edge shows the weight of the parent's synapse to that child, and any other synapse
+    /// is listed under its source as a cross-link, marked when it is disabled or recurrent.
+    /// Hidden neurons no input's tree reaches, because they have no tree position or their tree has
+    /// no input, are drawn under `Unplaced`.
+    pub fn render_tree(&self, charset: TreeCharset) -> String {
+        let mut renderer = TreeRenderer {
+            neurons: HashMap::new(),
+            outgoing: HashMap::new(),
+            branches: charset.branches(),
+            text: String::new(),
+        };
+        for gene in &self.genes {
+            match GeneType::from_u8(gene.flag[0]) {
+                Some(GeneType::Synapse) => renderer.outgoing.entry(gene.id).or_default().push((
+                    convert_f32_to_id(gene.local_data),
+                    gene.extern_data,
+                    gene.flag[1] == SynapseStatus::Enabled as u8,
+                )),
+                Some(_) => {
+                    renderer.neurons.insert(gene.id, gene);
+                }
+                None => {}
+            }
+        }
+        for links in renderer.outgoing.values_mut() {
+            links.sort_by_key(|link| link.0);
+        }
+
+        let mut ids: Vec<[u8; 2]> = renderer.neurons.keys().copied().collect();
+        ids.sort_by_key(|id| (id[0], get_neuron_height(id[1]), get_inorder_position(id[1] as u16)));
+        let of_kind = |gene_type: GeneType| -> Vec<[u8; 2]> {
+            ids.iter().copied().filter(|id| renderer.kind(id) == Some(gene_type)).collect()
+        };
+        let (inputs, hidden, outputs) = (of_kind(GeneType::Input), of_kind(GeneType::Hidden), of_kind(GeneType::Output));
+
+        for id in inputs {
+            renderer.text += &format!("Tree {}\n{}\n", id[0], renderer.label(id));
+            let mut children = renderer.children(id);
+            // Hidden neurons whose parent was removed hang off the root
+            children.extend(hidden.iter().filter(|other| {
+                other[0] == id[0] && get_tree_parent(**other).is_some_and(|parent| !renderer.neurons.contains_key(&parent))
+            }));
+            renderer.node(id, &children, "");
+        }
+
+        let unplaced: Vec<[u8; 2]> = hidden
+            .iter()
+            .copied()
+            .filter(|id| {
+                renderer.kind(&[id[0], 0]) != Some(GeneType::Input)
+                    && get_tree_parent(*id).is_none_or(|parent| !renderer.neurons.contains_key(&parent))
+            })
+            .collect();
+        if !unplaced.is_empty() {
+            renderer.text += "Unplaced\n";
+            for id in unplaced {
+                let reason = if get_tree_parent(id).is_none() { "no tree position" } else { "no input for its tree" };
+                renderer.text += &format!("{}  {}\n", renderer.label(id), reason);
+                let children = renderer.children(id);
+                renderer.node(id, &children, "");
+            }
+        }
+
+        if !outputs.is_empty() {
+            renderer.text += "Outputs\n";
+            for id in outputs {
+                let incoming = renderer.outgoing.values().flatten().filter(|link| link.0 == id).count();
+                renderer.text += &format!("{}  {} {} incoming\n", renderer.label(id), renderer.branches.incoming, incoming);
+            }
+        }
+        renderer.text
+    }
+}
+
+impl TreeRenderer<'_> {
+    fn kind(&self, id: &[u8; 2]) -> Option<GeneType> {
+        self.neurons.get(id).and_then(|gene| GeneType::from_u8(gene.flag[0]))
+    }
+
+    fn label(&self, id: [u8; 2]) -> String {
+        let gene = self.neurons[&id];
+        let kind = match GeneType::from_u8(gene.flag[0]) {
+            Some(GeneType::Input) => "input",
+            Some(GeneType::Output) => "output",
+            _ => "hidden",
+        };
+        format!("[{}:{}] {} bias {}", id[0], id[1], kind, gene.local_data)
+    }
+
+    /// Children of a tree position that exist in the genome, left first
+    fn children(&self, id: [u8; 2]) -> Vec<[u8; 2]> {
+        let position = id[1] as u16;
+        [2 * position + 1, 2 * position + 2]
+            .into_iter()
+            .filter(|&child| child <= u8::MAX as u16)
+            .map(|child| [id[0], child as u8])
+            .filter(|child| self.kind(child) == Some(GeneType::Hidden))
+            .collect()
+    }
+
+    /// Same order as `Network::activate`, used to spot links that read the previous step
+    fn update_order(&self, id: [u8; 2]) -> (u8, u32, [u8; 2]) {
+        let rank = match self.kind(&id) {
+            Some(GeneType::Input) => 0,
+            Some(GeneType::Output) => 2,
+            _ => 1,
+        };
+        (rank, get_neuron_height(id[1]), id)
+    }
+
+    fn node(&mut self, id: [u8; 2], children: &[[u8; 2]], prefix: &str) {
+        let links: Vec<Link> = self
+            .outgoing
+            .get(&id)
+            .into_iter()
+            .flatten()
+            .filter(|link| !children.contains(&link.0) || !self.is_tree_edge(id, link.0))
+            .copied()
+            .collect();
+        let link_prefix = format!("{}{}", prefix, if children.is_empty() { self.branches.blank } else { self.branches.pipe });
+        for (to, weight, enabled) in links {
+            let mut line = format!("{}  {} [{}:{}] w={:+.3}", link_prefix, self.branches.link, to[0], to[1], weight);
+            if !enabled {
+                line += " disabled";
+            }
+            if self.update_order(to) <= self.update_order(id) {
+                line += " recurrent";
+            }
+            self.text += &line;
+            self.text.push('\n');
+        }
+
+        for (index, &child) in children.iter().enumerate() {
+            let last = index + 1 == children.len();
+            let side = if !self.is_tree_edge(id, child) {
+                "?"
+            } else if child[1] % 2 == 1 {
+                "L"
+            } else {
+                "R"
+            };
+            let tree_link = self.outgoing.get(&id).and_then(|links| links.iter().find(|link| link.0 == child));
+            let edge = match tree_link {
+                _ if side == "?" => "  detached, parent removed".to_string(),
+                Some((_, weight, true)) => format!("  w={:+.3}", weight),
+                Some((_, weight, false)) => format!("  w={:+.3} disabled", weight),
+                None => "  no synapse from parent".to_string(),
+            };
+            let connector = if last { self.branches.corner } else { self.branches.tee };
+            self.text += &format!("{}{}{} {}{}\n", prefix, connector, side, self.label(child), edge);
+
+            let child_prefix = format!("{}{}", prefix, if last { self.branches.blank } else { self.branches.pipe });
+            let grandchildren = self.children(child);
+            self.node(child, &grandchildren, &child_prefix);
+        }
+    }
+
+    fn is_tree_edge(&self, parent: [u8; 2], child: [u8; 2]) -> bool {
+        get_tree_parent(child) == Some(parent)
+    }
+}
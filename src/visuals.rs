@@ -1,12 +1,75 @@
 use std::collections::HashMap;
+use std::io;
+use std::path::Path;
 
-use crate::{convert_f32_to_id, get_inorder_position, get_neuron_height, GeneType, Genome, SynapseStatus};
-use plotly::{common::{MarkerSymbol, Mode}, Plot, Scatter};
+use crate::{convert_f32_to_id, get_inorder_position, get_neuron_height, GeneType, Genome, Network, SynapseStatus};
+use plotly::{
+    common::{ColorBar, ColorScale, ColorScalePalette, DashType, HoverInfo, Line, Marker, MarkerSymbol, Mode, Position},
+    layout::Axis,
+    Layout, Plot, Scatter,
+};
+
+/// Output of the headless `visualize` variants
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlotFormat {
+    /// Standalone HTML page that loads plotly.js
+    Html,
+    /// Plotly figure JSON (`data` and `layout`)
+    Json,
+}
+
+/// What the neuron markers are colored by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NodeColor {
+    /// Fixed colors for input, hidden and output neurons
+    #[default]
+    Type,
+    /// Diverging scale centered on a bias of zero
+    Bias,
+    /// Diverging scale of the last activation. Only a `Network` has activations, genomes fall back to `Type`.
+    Activation,
+}
+
+/// Layout of the genome tree plot
+#[derive(Debug, Clone, Default)]
+pub struct VisualizeOptions {
+    pub title: Option<String>,
+    /// Figure size in pixels, None lets plotly fill the page
+    pub width: Option<usize>,
+    pub height: Option<usize>,
+    /// Hides the axes, grid and tick labels, which carry no meaning in the tree layout
+    pub hide_axes: bool,
+    /// Writes each neuron's id next to it instead of only on hover
+    pub node_labels: bool,
+    /// Colors synapses by weight sign and scales their width by magnitude instead of drawing them uniformly
+    pub weighted_edges: bool,
+    pub node_color: NodeColor,
+}
 
 // Debug display for genome
 impl Genome {
+    /// Opens the tree plot in a browser
     pub fn visualize(&self) {
-        let mut plot = Plot::new();
+        self.visualize_plot(&VisualizeOptions::default()).show();
+    }
+
+    /// Renders the tree plot without opening a browser
+    pub fn visualize_to_string(&self, options: &VisualizeOptions, format: PlotFormat) -> String {
+        render_plot(&self.visualize_plot(options), format)
+    }
+
+    /// Writes the tree plot to a file, as plotly JSON if the path ends in `.json` and as HTML otherwise
+    pub fn visualize_to_file<P: AsRef<Path>>(&self, path: P, options: &VisualizeOptions) -> io::Result<()> {
+        write_plot(&self.visualize_plot(options), path)
+    }
+
+    /// Builds the tree plot: inputs along the bottom, each input's hidden tree above it and outputs on top
+    pub fn visualize_plot(&self, options: &VisualizeOptions) -> Plot {
+        tree_plot(self, options, None)
+    }
+
+    /// Plot position of every neuron in the tree layout
+    pub fn tree_layout(&self) -> HashMap<[u8; 2], (f64, f64)> {
         let mut neuron_positions: HashMap<[u8; 2], (f64, f64)> = HashMap::new();
 
         // Constants for layout
@@ -15,169 +78,279 @@ impl Genome {
 
         // Collect neurons and compute positions
         for gene in &self.genes {
-            // Skip synapses
-            if GeneType::from_u8(gene.flag[0]) == Some(GeneType::Synapse) {
-                continue;
-            }
+            let (x_pos, y_pos) = match GeneType::from_u8(gene.flag[0]) {
+                Some(GeneType::Input) => ((gene.id[0] as f64 * horizontal_spacing) + 0.5, 0.0),
+                Some(GeneType::Hidden) => {
+                    let depth = get_neuron_height(gene.id[1]) as f64;
+                    let inorder_pos = get_inorder_position(gene.id[1] as u16) as f64;
+                    let max_nodes_at_depth = 2u32.pow(depth as u32) as f64;
+                    let x_pos = (inorder_pos + 0.5) * (horizontal_spacing / max_nodes_at_depth);
+                    (x_pos + gene.id[0] as f64 * horizontal_spacing, depth * vertical_spacing)
+                }
+                Some(GeneType::Output) => (255.0 - gene.id[0] as f64, (7.0 + 1.0) * vertical_spacing),
+                // Skip synapses
+                _ => continue,
+            };
+            neuron_positions.insert(gene.id, (x_pos, y_pos));
+        }
+        neuron_positions
+    }
+}
 
-            let neuron_type = GeneType::from_u8(gene.flag[0]).unwrap();
-            let mut x_pos = 0.0;
-            let mut y_pos = 0.0;
+impl Network {
+    /// Tree plot of the network's genome, which can color neurons by their current activation
+    pub fn visualize_plot(&self, options: &VisualizeOptions) -> Plot {
+        let activations = self.neurons.iter().map(|(id, neuron)| (*id, neuron.activation)).collect();
+        tree_plot(&self.genome, options, Some(&activations))
+    }
 
-            let tree_id = gene.id[0] as u16;
-            let node_id1 = gene.id[1] as u16;
+    pub fn visualize_to_string(&self, options: &VisualizeOptions, format: PlotFormat) -> String {
+        render_plot(&self.visualize_plot(options), format)
+    }
 
-            match neuron_type {
-                GeneType::Input => {
-                    x_pos = (tree_id as f64 * horizontal_spacing) + 0.5;
-                    y_pos = 0.0;
-                }
-                GeneType::Hidden => {
-                    let depth = get_neuron_height(node_id1 as u8) as f64;
-                    y_pos = depth * vertical_spacing;
+    pub fn visualize_to_file<P: AsRef<Path>>(&self, path: P, options: &VisualizeOptions) -> io::Result<()> {
+        write_plot(&self.visualize_plot(options), path)
+    }
+}
 
-                    let inorder_pos = get_inorder_position(node_id1) as f64;
-                    let max_nodes_at_depth = 2u32.pow(depth as u32) as f64;
+pub(crate) fn render_plot(plot: &Plot, format: PlotFormat) -> String {
+    match format {
+        PlotFormat::Html => plot.to_html(),
+        PlotFormat::Json => plot.to_json(),
+    }
+}
 
-                    x_pos = (inorder_pos + 0.5) * (horizontal_spacing / max_nodes_at_depth);
-                    x_pos += tree_id as f64 * horizontal_spacing;
-                }
-                GeneType::Output => {
-                    x_pos = 255.0 - tree_id as f64;
-                    y_pos = (7.0 + 1.0) * vertical_spacing;
-                }
-                _ => {}
-            }
+/// Writes plotly JSON if the path ends in `.json` and a standalone HTML page otherwise
+pub(crate) fn write_plot<P: AsRef<Path>>(plot: &Plot, path: P) -> io::Result<()> {
+    let format = match path.as_ref().extension() {
+        Some(extension) if extension.eq_ignore_ascii_case("json") => PlotFormat::Json,
+        _ => PlotFormat::Html,
+    };
+    std::fs::write(path, render_plot(plot, format))
+}
 
-            // Store positions
-            neuron_positions.insert(gene.id, (x_pos, y_pos));
-        }
+/// Applies the shared title, size and axis options
+pub(crate) fn apply_layout_options(mut layout: Layout, options: &VisualizeOptions) -> Layout {
+    if let Some(title) = &options.title {
+        layout = layout.title(title.as_str());
+    }
+    if let Some(width) = options.width {
+        layout = layout.width(width);
+    }
+    if let Some(height) = options.height {
+        layout = layout.height(height);
+    }
+    if options.hide_axes {
+        let hidden = || Axis::new().visible(false).show_grid(false).zero_line(false);
+        layout = layout.x_axis(hidden()).y_axis(hidden());
+    }
+    layout
+}
 
-        // Collect synapses
+fn tree_plot(genome: &Genome, options: &VisualizeOptions, activations: Option<&HashMap<[u8; 2], f32>>) -> Plot {
+    let mut plot = Plot::new();
+    let neuron_positions = genome.tree_layout();
+
+    // Collect synapses that connect two placed neurons
+    let synapses: Vec<([u8; 2], [u8; 2], f32, bool)> = genome
+        .genes
+        .iter()
+        .filter(|gene| GeneType::from_u8(gene.flag[0]) == Some(GeneType::Synapse))
+        .map(|gene| {
+            let enabled = gene.flag[1] == SynapseStatus::Enabled as u8;
+            (gene.id, convert_f32_to_id(gene.local_data), gene.extern_data, enabled)
+        })
+        .filter(|(from, to, _, _)| neuron_positions.contains_key(from) && neuron_positions.contains_key(to))
+        .collect();
+
+    if options.weighted_edges {
+        // One trace per synapse since plotly cannot vary color or width along a trace
+        let max_weight = synapses.iter().map(|synapse| synapse.2.abs()).fold(0.0f32, f32::max);
+        let mut shown_groups = Vec::new();
+        for (from, to, weight, enabled) in &synapses {
+            let (x0, y0) = neuron_positions[from];
+            let (x1, y1) = neuron_positions[to];
+            let width = if max_weight > 0.0 { 0.5 + 4.5 * (weight.abs() / max_weight) as f64 } else { 1.0 };
+            let (mut group, color) = if *weight >= 0.0 { ("Positive weight", "#2b6cb0") } else { ("Negative weight", "#c53030") };
+            let mut line = Line::new().color(color).width(width);
+            if !enabled {
+                group = "Disabled";
+                line = line.dash(DashType::Dash);
+            }
+            let first_of_group = !shown_groups.contains(&group);
+            shown_groups.push(group);
+            let edge_trace = Scatter::new(vec![x0, x1], vec![y0, y1])
+                .mode(Mode::Lines)
+                .name(group)
+                .legend_group(group)
+                .show_legend(first_of_group)
+                .opacity(if *enabled { 1.0 } else { 0.5 })
+                .line(line)
+                .hover_info(HoverInfo::None);
+            plot.add_trace(edge_trace);
+        }
+    } else {
         let mut edge_x_enabled = Vec::new();
         let mut edge_y_enabled = Vec::new();
         let mut edge_x_disabled = Vec::new();
         let mut edge_y_disabled = Vec::new();
-
-        for gene in &self.genes {
-            if GeneType::from_u8(gene.flag[0]) != Some(GeneType::Synapse) {
-                continue;
-            }
-
-            let synapse_status = if gene.flag[1] == SynapseStatus::Enabled as u8 {
-                SynapseStatus::Enabled
+        for (from, to, _, enabled) in &synapses {
+            let (x0, y0) = neuron_positions[from];
+            let (x1, y1) = neuron_positions[to];
+            let (edge_x, edge_y) = if *enabled {
+                (&mut edge_x_enabled, &mut edge_y_enabled)
             } else {
-                SynapseStatus::Disabled
+                (&mut edge_x_disabled, &mut edge_y_disabled)
             };
-
-            let source_id = gene.id;
-            let destination_id = convert_f32_to_id(gene.local_data);
-
-            if let (Some(&(x0, y0)), Some(&(x1, y1))) =
-                (neuron_positions.get(&source_id), neuron_positions.get(&destination_id))
-            {
-                match synapse_status {
-                    SynapseStatus::Enabled => {
-                        edge_x_enabled.push(x0);
-                        edge_x_enabled.push(x1);
-                        edge_x_enabled.push(f64::NAN);
-                        edge_y_enabled.push(y0);
-                        edge_y_enabled.push(y1);
-                        edge_y_enabled.push(f64::NAN);
-                    }
-                    SynapseStatus::Disabled => {
-                        edge_x_disabled.push(x0);
-                        edge_x_disabled.push(x1);
-                        edge_x_disabled.push(f64::NAN);
-                        edge_y_disabled.push(y0);
-                        edge_y_disabled.push(y1);
-                        edge_y_disabled.push(f64::NAN);
-                    }
-                }
-            }
+            edge_x.extend([x0, x1, f64::NAN]);
+            edge_y.extend([y0, y1, f64::NAN]);
         }
 
         // Trace for enabled synapses
         let edge_trace_enabled = Scatter::new(edge_x_enabled, edge_y_enabled)
             .mode(Mode::Lines)
             .name("Enabled Synapses")
-            .line(plotly::common::Line::new().color("#888").width(1.0))
-            .hover_info(plotly::common::HoverInfo::None);
-
+            .line(Line::new().color("#888").width(1.0))
+            .hover_info(HoverInfo::None);
         plot.add_trace(edge_trace_enabled);
 
         // Trace for disabled synapses
         let edge_trace_disabled = Scatter::new(edge_x_disabled, edge_y_disabled)
             .mode(Mode::Lines)
             .name("Disabled Synapses")
-            .line(
-                plotly::common::Line::new()
-                    .color("#ff0000")
-                    .width(1.0)
-                    .dash(plotly::common::DashType::Dash),
-            )
-            .hover_info(plotly::common::HoverInfo::None);
-
+            .line(Line::new().color("#ff0000").width(1.0).dash(DashType::Dash))
+            .hover_info(HoverInfo::None);
         plot.add_trace(edge_trace_disabled);
+    }
 
-        // Collect neuron positions and types for node trace
-        let mut node_x = Vec::new();
-        let mut node_y = Vec::new();
-        let mut node_text = Vec::new();
-        let mut node_colors = Vec::new();
-        let mut node_symbols = Vec::new();
+    // Invisible markers at synapse midpoints carry the synapse hover text
+    let midpoints: Vec<(f64, f64)> = synapses
+        .iter()
+        .map(|(from, to, _, _)| {
+            let ((x0, y0), (x1, y1)) = (neuron_positions[from], neuron_positions[to]);
+            ((x0 + x1) / 2.0, (y0 + y1) / 2.0)
+        })
+        .collect();
+    let synapse_text = synapses
+        .iter()
+        .map(|(from, to, weight, enabled)| {
+            let status = if *enabled { "enabled" } else { "disabled" };
+            format!("Synapse {:?} -> {:?}<br>Weight: {}<br>{}", from, to, weight, status)
+        })
+        .collect();
+    let synapse_trace = Scatter::new(
+        midpoints.iter().map(|point| point.0).collect(),
+        midpoints.iter().map(|point| point.1).collect(),
+    )
+    .mode(Mode::Markers)
+    .name("Synapses")
+    .show_legend(false)
+    .marker(Marker::new().size(8).color("rgba(0,0,0,0)"))
+    .hover_text_array(synapse_text)
+    .hover_info(HoverInfo::Text);
+    plot.add_trace(synapse_trace);
 
-        for gene in &self.genes {
-            if GeneType::from_u8(gene.flag[0]) == Some(GeneType::Synapse) {
-                continue;
-            }
+    // Collect neuron positions and types for node trace
+    let mut node_x = Vec::new();
+    let mut node_y = Vec::new();
+    let mut node_text = Vec::new();
+    let mut node_labels = Vec::new();
+    let mut node_colors = Vec::new();
+    let mut node_values = Vec::new();
 
-            let neuron_type = GeneType::from_u8(gene.flag[0]).unwrap();
-            let (x, y) = neuron_positions[&gene.id];
-            node_x.push(x);
-            node_y.push(y);
-            node_text.push(format!("ID: {:?}, Type: {:?}", gene.id, neuron_type));
-
-            // Assign colors or symbols based on neuron type
-            match neuron_type {
-                GeneType::Input => {
-                    node_colors.push("blue");
-                    node_symbols.push(MarkerSymbol::Circle);
-                }
-                GeneType::Hidden => {
-                    node_colors.push("green");
-                    node_symbols.push(MarkerSymbol::Square);
-                }
-                GeneType::Output => {
-                    node_colors.push("red");
-                    node_symbols.push(MarkerSymbol::Diamond);
-                }
-                _ => {}
-            }
+    for gene in &genome.genes {
+        let neuron_type = match GeneType::from_u8(gene.flag[0]) {
+            Some(GeneType::Synapse) | None => continue,
+            Some(neuron_type) => neuron_type,
+        };
+        let (x, y) = neuron_positions[&gene.id];
+        node_x.push(x);
+        node_y.push(y);
+        let activation = activations.and_then(|activations| activations.get(&gene.id).copied());
+        let mut text = format!(
+            "ID: {:?}, Type: {:?}<br>Bias: {}<br>Children: {}<br>Tree {}, height {}, in-order position {}",
+            gene.id,
+            neuron_type,
+            gene.local_data,
+            gene.flag[1],
+            gene.id[0],
+            get_neuron_height(gene.id[1]),
+            get_inorder_position(gene.id[1] as u16)
+        );
+        if let Some(activation) = activation {
+            text += &format!("<br>Activation: {}", activation);
+        }
+        node_text.push(text);
+        node_labels.push(format!("{}:{}", gene.id[0], gene.id[1]));
+        node_values.push(match options.node_color {
+            NodeColor::Activation => activation.unwrap_or(0.0) as f64,
+            _ => gene.local_data as f64,
+        });
+
+        // Assign colors based on neuron type
+        node_colors.push(match neuron_type {
+            GeneType::Input => "blue",
+            GeneType::Hidden => "green",
+            _ => "red",
+        });
+    }
+
+    let color_title = match options.node_color {
+        NodeColor::Bias => Some("Bias"),
+        NodeColor::Activation if activations.is_some() => Some("Activation"),
+        _ => None,
+    };
+    let marker = Marker::new().size(10).symbol(MarkerSymbol::Star);
+    let marker = match color_title {
+        Some(title) => {
+            let limit = node_values.iter().fold(1e-6f64, |limit, value| limit.max(value.abs()));
+            marker
+                .size(14)
+                .symbol(MarkerSymbol::Circle)
+                .color_array(node_values)
+                .color_scale(ColorScale::Palette(ColorScalePalette::RdBu))
+                .cmin(-limit)
+                .cmax(limit)
+                .show_scale(true)
+                .color_bar(ColorBar::new().title(title))
+                .line(Line::new().color("#444").width(1.0))
         }
+        None => marker.color_array(node_colors),
+    };
 
-        // Node trace
-        let node_trace = Scatter::new(node_x, node_y)
-            .mode(Mode::Markers)
-            .name("Neurons")
-            .marker(
-                plotly::common::Marker::new()
-                    .size(10)
-                    .color_array(node_colors)
-                    .symbol(MarkerSymbol::Star),
-            )
-            .text_array(node_text)
-            .hover_info(plotly::common::HoverInfo::Text);
-
-        plot.add_trace(node_trace);
-
-        plot.show();
+    // Node trace
+    let mut node_trace = Scatter::new(node_x, node_y)
+        .mode(Mode::Markers)
+        .name("Neurons")
+        .marker(marker)
+        .hover_text_array(node_text)
+        .hover_info(HoverInfo::Text);
+    if options.node_labels {
+        node_trace = node_trace
+            .mode(Mode::MarkersText)
+            .text_array(node_labels)
+            .text_position(Position::TopCenter);
     }
+    plot.add_trace(node_trace);
+
+    plot.set_layout(apply_layout_options(Layout::new(), options));
+    plot
+}
+
+// Text output and statistics for genome
+impl Genome {
     pub fn display(&self) {
+        self.print_display(false);
+    }
+
+    /// Same as `display` with the genome's `to_code` line before the footer
+    pub fn display_with_code(&self) {
+        self.print_display(true);
+    }
+
+    fn print_display(&self, with_code: bool) {
         println!("################ GENOME DISPLAY ################");
         for gene in &self.genes {
-            let seed = gene.id[0];
-            let id = gene.id[1];
             let flag1 = gene.flag[0];
             let flag2 = gene.flag[1];
             let lcl_data = gene.local_data;
@@ -209,6 +382,9 @@ impl Genome {
                 println!("  L SYNAPSE- - - - - - # ID_FROM {:?} # ID_TO {:?} # WEIGHT {} # ENABLED: [{}]", gene.id, to_id, ext_data, enabled)
             }
         }
+        if with_code {
+            println!("CODE: {}", self.to_code());
+        }
         println!("################################################\n");
     }
     pub fn debug_display(&self) {
@@ -231,8 +407,7 @@ impl Genome {
             }
         }
         let total_possible_neurons = input_nuerons * 254;
-        let complexity = (hidden_nuerons as f32 / total_possible_neurons as f32) * 100.0;
-        complexity 
+        (hidden_nuerons as f32 / total_possible_neurons as f32) * 100.0
     }
     
     /// Outputs statistics about the genome, including counts of neurons and synapses.
@@ -280,7 +455,6 @@ impl Genome {
         
         let total_neurons = input_neurons + hidden_neurons + output_neurons;
         let total_synapses = enabled_synapses + disabled_synapses;
-        let complexity = (hidden_neurons as f32 / (254.0 * input_neurons as f32)) * 100.0;
         
         println!("##### Genome Statistics #####");
         println!("Total neurons: {}", total_neurons);
@@ -0,0 +1,99 @@
+mod common;
+
+use aster_ml::{Genome, JsonValue, VisualizeOptions};
+use common::{hidden, synapse};
+
+/// Parents that share their input and output. A adds [0, 1], B adds [0, 2] and a disabled direct
+/// synapse. The child keeps A's first synapse and B's disabled one, and adds [0, 3] of its own.
+fn family() -> (Genome, Genome, Genome) {
+    let mut parent_a = Genome::new(1, 1);
+    parent_a.genes[0].flag[1] = 1;
+    parent_a.genes.extend([hidden([0, 1], 0, 0.0), synapse([0, 0], [0, 1], 1.0, true), synapse([0, 1], [255, 0], 1.0, true)]);
+
+    let mut parent_b = Genome::new(1, 1);
+    parent_b.genes[0].flag[1] = 1;
+    parent_b.genes.extend([hidden([0, 2], 0, 0.0), synapse([0, 0], [0, 2], -1.0, true), synapse([0, 0], [255, 0], 0.5, false)]);
+
+    let mut child = Genome::new(1, 1);
+    child.genes[0].flag[1] = 1;
+    child.genes.extend([
+        hidden([0, 1], 1, 0.0),
+        hidden([0, 3], 0, 0.0),
+        synapse([0, 0], [0, 1], 1.0, true),
+        synapse([0, 0], [255, 0], 0.5, false),
+        synapse([0, 3], [255, 0], 2.0, true),
+    ]);
+    (parent_a, parent_b, child)
+}
+
+fn text(value: Option<&JsonValue>) -> String {
+    match value {
+        Some(JsonValue::String(text)) => text.clone(),
+        other => panic!("expected a string, got {:?}", other),
+    }
+}
+
+/// Gene traces as (panel axis, group, lines or markers, opacity, dashed, point count), skipping the
+/// invisible synapse hover markers
+fn gene_traces(json: &str) -> Vec<(String, String, String, String, bool, usize)> {
+    let figure: JsonValue = json.parse().unwrap();
+    let Some(JsonValue::Array(traces)) = figure.get("data") else { panic!("expected a data array") };
+    traces
+        .iter()
+        .filter(|trace| text(trace.get("name")) != "Synapses")
+        .map(|trace| {
+            let Some(JsonValue::Array(points)) = trace.get("x") else { panic!("expected x values") };
+            let opacity = match trace.get("opacity") {
+                Some(JsonValue::Number(opacity)) => opacity.clone(),
+                other => panic!("expected an opacity, got {:?}", other),
+            };
+            let dashed = trace.get("line").and_then(|line| line.get("dash")).is_some();
+            (text(trace.get("xaxis")), text(trace.get("name")), text(trace.get("mode")), opacity, dashed, points.len())
+        })
+        .collect()
+}
+
+#[test]
+fn crossover_plot_groups_genes_by_alignment() {
+    let (parent_a, parent_b, child) = family();
+    let plot = Genome::crossover_plot(&parent_a, &parent_b, &child, &VisualizeOptions::default());
+    let traces = gene_traces(&plot.to_json());
+    let expected = [
+        // Parent A: [0, 1] -> [255, 0] was not passed on
+        ("x", "Disjoint, parent A", "lines", "0.3", false, 3),
+        ("x", "Disjoint, parent A", "lines", "1.0", false, 3),
+        ("x", "Matching", "markers", "1.0", false, 2),
+        ("x", "Disjoint, parent A", "markers", "1.0", false, 1),
+        // Parent B: [0, 2] and its synapse were not passed on, the disabled synapse was
+        ("x2", "Disjoint, parent B", "lines", "1.0", true, 3),
+        ("x2", "Disjoint, parent B", "lines", "0.3", false, 3),
+        ("x2", "Matching", "markers", "1.0", false, 2),
+        ("x2", "Disjoint, parent B", "markers", "0.3", false, 1),
+        // Child: everything it has is drawn in full
+        ("x3", "Disjoint, parent A", "lines", "1.0", false, 3),
+        ("x3", "Disjoint, parent B", "lines", "1.0", true, 3),
+        ("x3", "In neither parent", "lines", "1.0", false, 3),
+        ("x3", "Matching", "markers", "1.0", false, 2),
+        ("x3", "Disjoint, parent A", "markers", "1.0", false, 1),
+        ("x3", "In neither parent", "markers", "1.0", false, 1),
+    ]
+    .map(|(axis, group, mode, opacity, dashed, points)| {
+        (axis.to_string(), group.to_string(), mode.to_string(), opacity.to_string(), dashed, points)
+    });
+    assert_eq!(traces, expected);
+}
+
+#[test]
+fn crossover_plot_shows_each_group_in_the_legend_once() {
+    let (parent_a, parent_b, child) = family();
+    let plot = Genome::crossover_plot(&parent_a, &parent_b, &child, &VisualizeOptions::default());
+    let figure: JsonValue = plot.to_json().parse().unwrap();
+    let Some(JsonValue::Array(traces)) = figure.get("data") else { panic!("expected a data array") };
+    let mut shown: Vec<String> = traces
+        .iter()
+        .filter(|trace| trace.get("showlegend") == Some(&JsonValue::Bool(true)))
+        .map(|trace| text(trace.get("name")))
+        .collect();
+    shown.sort();
+    assert_eq!(shown, ["Disjoint, parent A", "Disjoint, parent B", "In neither parent", "Matching"]);
+}
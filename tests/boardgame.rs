@@ -0,0 +1,130 @@
+mod common;
+
+use aster_ml::{play_game, round_robin, BoardGame, ConnectFour, Genome, Network, TicTacToe};
+
+/// Network without synapses that prefers moves in the given order, whatever the board
+fn preferring<G: BoardGame>(order: &[usize]) -> Network {
+    let mut genome = Genome::new(G::CELLS, G::MOVES);
+    for gene in genome.genes.iter_mut().filter(|gene| gene.flag[0] == 3) {
+        let mv = 255 - gene.id[0] as usize;
+        let rank = order.iter().position(|&preferred| preferred == mv).unwrap_or(order.len());
+        gene.local_data = 1.0 - rank as f32 * 0.2;
+    }
+    Network::from_genome(genome)
+}
+
+fn connect_four(stones: &[(usize, usize)], owner: usize) -> ConnectFour {
+    let mut game = ConnectFour::new();
+    for &(column, row) in stones {
+        game.cells[row * ConnectFour::COLUMNS + column] = Some(owner);
+    }
+    game
+}
+
+#[test]
+fn connect_four_wins_in_every_direction() {
+    let lines: [[(usize, usize); 4]; 8] = [
+        // Horizontal and vertical, against the right and top edges
+        [(3, 0), (4, 0), (5, 0), (6, 0)],
+        [(6, 2), (6, 3), (6, 4), (6, 5)],
+        // Rising diagonals from each bottom corner region
+        [(0, 0), (1, 1), (2, 2), (3, 3)],
+        [(3, 2), (4, 3), (5, 4), (6, 5)],
+        // Falling diagonals touching the left and right edges
+        [(0, 5), (1, 4), (2, 3), (3, 2)],
+        [(3, 3), (4, 2), (5, 1), (6, 0)],
+        [(0, 3), (1, 2), (2, 1), (3, 0)],
+        [(3, 5), (4, 4), (5, 3), (6, 2)],
+    ];
+    for line in lines {
+        assert_eq!(connect_four(&line, 1).winner(), Some(1), "{:?}", line);
+        assert_eq!(connect_four(&line[..3], 1).winner(), None, "{:?}", line);
+    }
+}
+
+#[test]
+fn connect_four_needs_one_owner() {
+    let mut game = connect_four(&[(0, 0), (1, 1), (2, 2)], 0);
+    game.cells[3 * ConnectFour::COLUMNS + 3] = Some(1);
+    assert_eq!(game.winner(), None);
+    // Lines do not wrap from one row to the next
+    assert_eq!(connect_four(&[(5, 0), (6, 0), (0, 1), (1, 1)], 0).winner(), None);
+}
+
+#[test]
+fn connect_four_skips_full_columns() {
+    let mut game = ConnectFour::new();
+    for _ in 0..ConnectFour::ROWS {
+        game.play(0);
+    }
+    assert_eq!(game.winner(), None);
+    assert_eq!(game.legal_moves(), vec![1, 2, 3, 4, 5, 6]);
+
+    // Both players want column 0; once it is full they move on to column 1 and so on
+    let order = [0, 1, 2, 3, 4, 5, 6];
+    let scores = play_game::<ConnectFour>(&mut preferring::<ConnectFour>(&order), &mut preferring::<ConnectFour>(&order));
+    assert_eq!(scores, [1.0, 0.0]);
+}
+
+#[test]
+fn tic_tac_toe_wins_on_every_line() {
+    let lines = [[0, 1, 2], [3, 4, 5], [6, 7, 8], [0, 3, 6], [1, 4, 7], [2, 5, 8], [0, 4, 8], [2, 4, 6]];
+    for line in lines {
+        let mut game = TicTacToe::new();
+        for cell in line {
+            game.cells[cell] = Some(0);
+        }
+        assert_eq!(game.winner(), Some(0), "{:?}", line);
+        assert!(game.legal_moves().is_empty());
+        game.cells[line[1]] = Some(1);
+        assert_eq!(game.winner(), None, "{:?}", line);
+    }
+}
+
+#[test]
+fn full_board_without_a_line_is_a_draw() {
+    // X O X
+    // X O O
+    // O X X
+    let mut first = preferring::<TicTacToe>(&[0, 2, 3, 7, 8]);
+    let mut second = preferring::<TicTacToe>(&[1, 4, 5, 6]);
+    assert_eq!(play_game::<TicTacToe>(&mut first, &mut second), [0.5, 0.5]);
+
+    let mut game = TicTacToe::new();
+    for mv in [0, 1, 2, 4, 3, 5, 7, 6, 8] {
+        assert!(!game.is_over());
+        game.play(mv);
+    }
+    assert!(game.is_over());
+    assert_eq!(game.winner(), None);
+}
+
+#[test]
+fn round_robin_hands_out_every_point() {
+    let mut networks: Vec<Network> = (0..5)
+        .map(|seed| Network::from_genome(common::random_genome(seed, TicTacToe::CELLS, TicTacToe::MOVES, 15)))
+        .collect();
+    let scores = round_robin::<TicTacToe>(&mut networks);
+    assert_eq!(scores.len(), 5);
+    assert!(scores.iter().all(|score| (0.0..=1.0).contains(score)));
+    // Every game hands out one point and each network plays 2 games against the 4 others
+    let total: f32 = scores.iter().map(|score| score * 8.0).sum();
+    assert!((total - 20.0).abs() < 1e-4, "{}", total);
+
+    assert_eq!(round_robin::<TicTacToe>(&mut [TicTacToe::new_network()]), vec![0.0]);
+    assert!(round_robin::<TicTacToe>(&mut []).is_empty());
+}
+
+#[test]
+fn round_robin_scores_known_players() {
+    // The draw pairing from above, plus a player taking the lowest free cell.
+    // Each network plays 4 games, so the mean scores sum to 3 pairings * 2 games / 4.
+    let mut networks = [
+        preferring::<TicTacToe>(&[0, 2, 3, 7, 8]),
+        preferring::<TicTacToe>(&[1, 4, 5, 6]),
+        preferring::<TicTacToe>(&[]),
+    ];
+    let scores = round_robin::<TicTacToe>(&mut networks);
+    assert_eq!(scores, vec![0.375, 0.375, 0.75]);
+    assert_eq!(scores.iter().sum::<f32>(), 1.5);
+}
@@ -0,0 +1,39 @@
+mod common;
+
+use aster_ml::{Genome, GenomeError, GENOME_CODE_PREFIX};
+
+#[test]
+fn codes_round_trip() {
+    for seed in 0..10 {
+        let genome = common::churned_genome(seed, 3, 2, 40);
+        let code = genome.to_code();
+        assert!(code.starts_with(GENOME_CODE_PREFIX));
+        common::assert_same_genes(&genome, &Genome::from_code(&code).unwrap());
+
+        // Codes wrapped across lines or indented still load
+        let lines: Vec<&str> = code.as_bytes().chunks(20).map(|chunk| std::str::from_utf8(chunk).unwrap()).collect();
+        let wrapped = lines.join("\n");
+        common::assert_same_genes(&genome, &Genome::from_code(&format!("  {}\n", wrapped)).unwrap());
+    }
+}
+
+#[test]
+fn changed_code_fails_the_checksum() {
+    let code = common::random_genome(1, 2, 1, 10).to_code();
+    let middle = GENOME_CODE_PREFIX.len() + 10;
+    let replacement = if &code[middle..middle + 1] == "A" { "B" } else { "A" };
+    let changed = format!("{}{}{}", &code[..middle], replacement, &code[middle + 1..]);
+    assert!(matches!(Genome::from_code(&changed), Err(GenomeError::ChecksumMismatch { .. })));
+}
+
+#[test]
+fn rejects_codes_without_prefix_or_data() {
+    let code = Genome::new(2, 1).to_code();
+    let payload = &code[GENOME_CODE_PREFIX.len()..];
+    assert!(matches!(Genome::from_code(payload), Err(GenomeError::MissingCodePrefix)));
+    assert!(matches!(Genome::from_code(&format!("other:{}", payload)), Err(GenomeError::MissingCodePrefix)));
+    assert!(matches!(Genome::from_code("aster:not*base64"), Err(GenomeError::InvalidBase64(_))));
+    assert!(matches!(Genome::from_code("aster:AAA"), Err(GenomeError::Truncated { .. })));
+    // Dropping the end of a copied code loses the checksum
+    assert!(Genome::from_code(&code[..code.len() - 8]).is_err());
+}
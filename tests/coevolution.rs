@@ -0,0 +1,70 @@
+mod common;
+
+use aster_ml::{BoardGame, Coevolution, Genome, HallOfFame, TicTacToe};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// Genomes told apart by their input count
+fn marked(inputs: u16) -> Genome {
+    Genome::new(inputs, 1)
+}
+
+fn inputs(members: &[Genome]) -> Vec<usize> {
+    members.iter().map(|genome| genome.genes.iter().filter(|gene| gene.flag[0] == 1).count()).collect()
+}
+
+#[test]
+fn hall_of_fame_drops_the_oldest_champion() {
+    let mut hall_of_fame = HallOfFame::new(2);
+    for count in 1..=4 {
+        hall_of_fame.add(marked(count));
+    }
+    assert_eq!(inputs(&hall_of_fame.members), vec![3, 4]);
+    let networks = hall_of_fame.networks();
+    assert_eq!(networks.iter().map(|network| network.input_count()).collect::<Vec<_>>(), vec![3, 4]);
+
+    let mut rng = StdRng::seed_from_u64(0);
+    assert_eq!(hall_of_fame.sample(5, &mut rng).len(), 2);
+    let sampled = hall_of_fame.sample(1, &mut rng);
+    assert_eq!(sampled.len(), 1);
+    assert!([3, 4].contains(&sampled[0].input_count()));
+}
+
+#[test]
+fn empty_hall_of_fame_keeps_nothing() {
+    let mut hall_of_fame = HallOfFame::new(0);
+    hall_of_fame.add(marked(1));
+    assert!(hall_of_fame.members.is_empty());
+    assert!(hall_of_fame.sample(3, &mut StdRng::seed_from_u64(0)).is_empty());
+}
+
+#[test]
+fn generations_fill_the_hall_of_fame() {
+    let population: Vec<Genome> = (0..4)
+        .map(|seed| common::random_genome(seed, TicTacToe::CELLS, TicTacToe::MOVES, 15))
+        .collect();
+    let mut coevolution = Coevolution::new(2);
+    for generation in 0..3 {
+        let fitness = coevolution.record_generation::<TicTacToe>(generation, &population);
+        assert_eq!(fitness, coevolution.tournament_fitness::<TicTacToe>(&population));
+        let record = coevolution.history.last().unwrap();
+        assert_eq!(record.generation, generation);
+        assert_eq!(record.champion_tournament_score, fitness[record.champion]);
+        assert!(fitness.iter().all(|&score| score <= record.champion_tournament_score));
+        assert!((record.mean_tournament_score - 0.5).abs() < 1e-6);
+        assert_eq!(record.champion_vs_hall_of_fame.is_some(), generation > 0);
+        assert_eq!(coevolution.hall_of_fame.members.len(), (generation + 1).min(2));
+    }
+
+    let fitness = coevolution.hall_of_fame_fitness::<TicTacToe, _>(&population, 2, &mut StdRng::seed_from_u64(1));
+    assert_eq!(fitness.len(), population.len());
+    assert!(fitness.iter().all(|score| (0.0..=1.0).contains(score)));
+}
+
+#[test]
+fn empty_population_records_nothing() {
+    let mut coevolution = Coevolution::new(3);
+    assert!(coevolution.record_generation::<TicTacToe>(0, &[]).is_empty());
+    assert!(coevolution.history.is_empty());
+    assert!(coevolution.hall_of_fame.members.is_empty());
+}
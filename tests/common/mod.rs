@@ -0,0 +1,86 @@
+#![allow(dead_code)]
+
+use aster_ml::{convert_id_to_f32, Gene, Genome};
+use rand::prelude::*;
+use rand::rngs::StdRng;
+
+/// Genome grown by feed-forward mutations, with random biases and weights so its networks
+/// produce varied, non-zero outputs
+pub fn random_genome(seed: u64, inputs: u16, outputs: u16, mutations: usize) -> Genome {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut genome = Genome::new(inputs, outputs);
+    for _ in 0..mutations {
+        match rng.gen_range(0..3) {
+            0 => genome.random_child(),
+            1 => genome.rand_connected_child(),
+            _ => genome.random_synapse(),
+        }
+    }
+    randomize_values(&mut genome, &mut rng);
+    genome
+}
+
+/// Same as `random_genome` with recurrent synapses added as well
+pub fn random_recurrent_genome(seed: u64, inputs: u16, outputs: u16, mutations: usize) -> Genome {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut genome = random_genome(seed, inputs, outputs, mutations);
+    for _ in 0..mutations / 2 {
+        genome.random_recurrent_synapse();
+    }
+    randomize_values(&mut genome, &mut rng);
+    genome
+}
+
+fn randomize_values(genome: &mut Genome, rng: &mut StdRng) {
+    for gene in &mut genome.genes {
+        randomize_gene(gene, rng);
+    }
+}
+
+/// Neuron biases and synapse weights in [-2, 2]
+fn randomize_gene(gene: &mut Gene, rng: &mut StdRng) {
+    if gene.flag[0] == 10 {
+        gene.extern_data = rng.gen_range(-2.0..2.0);
+    } else if gene.flag[0] != 1 {
+        gene.local_data = rng.gen_range(-2.0..2.0);
+    }
+}
+
+/// Genome grown with every mutation operator, including disabling, re-enabling and removing synapses
+pub fn churned_genome(seed: u64, inputs: u16, outputs: u16, mutations: usize) -> Genome {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut genome = Genome::new(inputs, outputs);
+    for _ in 0..mutations {
+        match rng.gen_range(0..7) {
+            0 => genome.random_child(),
+            1 => genome.rand_connected_child(),
+            2 => genome.random_synapse(),
+            3 => genome.random_recurrent_synapse(),
+            4 => genome.disable_random_synapse(),
+            5 => genome.enable_random_synapse(),
+            _ => genome.remove_random_disabled_synapse(),
+        }
+    }
+    randomize_values(&mut genome, &mut rng);
+    genome
+}
+
+/// Asserts both genomes have the same genes in the same order, comparing values bit for bit
+pub fn assert_same_genes(left: &Genome, right: &Genome) {
+    assert_eq!(left.genes.len(), right.genes.len());
+    for (left, right) in left.genes.iter().zip(&right.genes) {
+        assert_eq!((left.id, left.flag), (right.id, right.flag));
+        assert_eq!(left.local_data.to_bits(), right.local_data.to_bits(), "{:?}", left.id);
+        assert_eq!(left.extern_data.to_bits(), right.extern_data.to_bits(), "{:?}", left.id);
+    }
+}
+
+/// Hidden neuron gene with `children` tree children and the given bias
+pub fn hidden(id: [u8; 2], children: u8, bias: f32) -> Gene {
+    Gene { id, flag: [2, children], local_data: bias, extern_data: 0.0 }
+}
+
+/// Synapse gene from `from` to `to`
+pub fn synapse(from: [u8; 2], to: [u8; 2], weight: f32, enabled: bool) -> Gene {
+    Gene { id: from, flag: [10, if enabled { 10 } else { 11 }], local_data: convert_id_to_f32(to), extern_data: weight }
+}
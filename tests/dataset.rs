@@ -0,0 +1,92 @@
+mod common;
+
+use aster_ml::{CsvOptions, Dataset, DatasetError, TargetColumn, TargetKind};
+
+const IRIS_LIKE: &str = "\
+sepal,petal,\"species\"
+5.1,1.4,b
+
+4.9,1.5,10
+6.3,4.9,2
+5.8,5.1,b
+";
+
+#[test]
+fn parses_csv_with_detected_header() {
+    let dataset = Dataset::parse_csv(IRIS_LIKE, &CsvOptions::default()).unwrap();
+    assert_eq!(dataset.feature_names, ["sepal", "petal"]);
+    assert_eq!(dataset.features, [[5.1, 1.4], [4.9, 1.5], [6.3, 4.9], [5.8, 5.1]]);
+    // Classes keep their order of first appearance, so "10" does not sort before "2"
+    assert_eq!(dataset.class_names, ["b", "10", "2"]);
+    assert_eq!(dataset.targets[2], [0.0, 0.0, 1.0]);
+    assert_eq!(dataset.targets[3], dataset.targets[0]);
+}
+
+#[test]
+fn detects_a_missing_header_and_honors_options() {
+    let text = "1;2;0.5\n3;4;1.5\n";
+    let options = CsvOptions { delimiter: ';', kind: TargetKind::Regression, ..Default::default() };
+    let dataset = Dataset::parse_csv(text, &options).unwrap();
+    assert_eq!(dataset.feature_names, ["x0", "x1"]);
+    assert_eq!(dataset.targets, [[0.5], [1.5]]);
+    assert!(dataset.class_names.is_empty());
+
+    let options = CsvOptions { target: TargetColumn::Index(0), has_header: Some(false), ..options };
+    let dataset = Dataset::parse_csv(text, &options).unwrap();
+    assert_eq!(dataset.features, [[2.0, 0.5], [4.0, 1.5]]);
+    assert_eq!(dataset.targets, [[1.0], [3.0]]);
+}
+
+#[test]
+fn reports_malformed_rows() {
+    let options = CsvOptions::default();
+    assert!(matches!(
+        Dataset::parse_csv("a,b,c\n1,2,x\n3,4\n", &options),
+        Err(DatasetError::RaggedRow { line: 3, expected: 3, found: 2 })
+    ));
+    assert!(matches!(
+        Dataset::parse_csv("a,b,c\n1,oops,x\n", &options),
+        Err(DatasetError::NotANumber { line: 2, column: 2, .. })
+    ));
+    assert!(matches!(Dataset::parse_csv("\n \n", &options), Err(DatasetError::Empty)));
+    let options = CsvOptions { target: TargetColumn::Name("label".to_string()), ..Default::default() };
+    assert!(matches!(Dataset::parse_csv("a,b\n1,2\n", &options), Err(DatasetError::MissingTargetColumn(_))));
+}
+
+fn numbered(rows: usize) -> Dataset {
+    let text: String = (0..rows).map(|row| format!("{},{}\n", row, row % 3)).collect();
+    Dataset::parse_csv(&text, &CsvOptions::default()).unwrap()
+}
+
+#[test]
+fn splits_and_folds_depend_only_on_the_seed() {
+    let dataset = numbered(50);
+    let first = dataset.split(0.6, 0.2, 11);
+    let second = dataset.split(0.6, 0.2, 11);
+    assert_eq!(first.train.features, second.train.features);
+    assert_eq!(first.test.features, second.test.features);
+    assert_eq!((first.train.len(), first.validation.len(), first.test.len()), (30, 10, 10));
+    assert_ne!(first.train.features, dataset.split(0.6, 0.2, 12).train.features);
+
+    let folds = dataset.k_folds(5, 3).unwrap();
+    let again = dataset.k_folds(5, 3).unwrap();
+    let mut held_out: Vec<f32> = Vec::new();
+    for ((train, test), (train_again, test_again)) in folds.iter().zip(&again) {
+        assert_eq!(train.features, train_again.features);
+        assert_eq!(test.features, test_again.features);
+        assert_eq!(train.len() + test.len(), 50);
+        held_out.extend(test.features.iter().map(|row| row[0]));
+    }
+    held_out.sort_by(f32::total_cmp);
+    assert_eq!(held_out, (0..50).map(|row| row as f32).collect::<Vec<f32>>());
+}
+
+#[test]
+fn bad_fold_counts_are_errors() {
+    let dataset = numbered(4);
+    assert!(matches!(dataset.k_folds(1, 0), Err(DatasetError::InvalidFoldCount { k: 1, rows: 4 })));
+    assert!(matches!(dataset.k_folds(5, 0), Err(DatasetError::InvalidFoldCount { k: 5, rows: 4 })));
+    let genome = common::random_genome(0, 1, 3, 4);
+    let result = dataset.cross_validate(&genome, 9, 0, |_, _| 0.0);
+    assert!(matches!(result, Err(DatasetError::InvalidFoldCount { k: 9, rows: 4 })));
+}
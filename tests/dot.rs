@@ -0,0 +1,98 @@
+mod common;
+
+use aster_ml::{Genome, Network};
+use common::{hidden, synapse};
+
+/// Two inputs and one output. Tree 0 holds [0, 1] and [0, 2] at height 1 and [0, 3] below [0, 1].
+/// Tree 1 holds [1, 5], whose parent [1, 2] is missing.
+fn sample_genome() -> Genome {
+    let mut genome = Genome::new(2, 1);
+    genome.genes[0].flag[1] = 2;
+    genome.genes.extend([
+        hidden([0, 1], 1, 0.5),
+        hidden([0, 2], 0, 0.0),
+        hidden([0, 3], 0, 0.0),
+        hidden([1, 5], 0, 0.0),
+        synapse([0, 0], [0, 1], 2.0, true),
+        synapse([0, 1], [0, 3], -1.0, true),
+        synapse([0, 3], [255, 0], 0.4, true),
+        synapse([1, 0], [255, 0], 1.0, false),
+        synapse([1, 5], [255, 0], -0.5, true),
+    ]);
+    genome
+}
+
+/// The line drawing the edge between two nodes
+fn edge<'a>(dot: &'a str, from: &str, to: &str) -> &'a str {
+    let prefix = format!("{} -> {} [", from, to);
+    dot.lines().map(str::trim).find(|line| line.starts_with(&prefix)).unwrap_or_else(|| panic!("no edge {}", prefix))
+}
+
+/// The body of one tree cluster
+fn cluster(dot: &str, tree: u8) -> &str {
+    let start = dot.find(&format!("subgraph cluster_tree_{} {{", tree)).expect("cluster exists");
+    let end = start + dot[start..].find("\n    }\n").unwrap();
+    &dot[start..end]
+}
+
+#[test]
+fn genome_dot_has_one_cluster_per_input() {
+    let dot = sample_genome().to_dot();
+    assert!(dot.starts_with("digraph genome {"));
+    assert_eq!(dot.matches("subgraph cluster_tree_").count(), 2);
+    assert_eq!(dot.matches("subgraph cluster_tree_0 ").count(), 1);
+    assert_eq!(dot.matches("subgraph cluster_tree_1 ").count(), 1);
+    assert!(!cluster(&dot, 0).contains("n255_0"));
+    assert!(dot.contains("n255_0 [label=\"output [255, 0]\\nbias 0\", shape=doublecircle"));
+    assert!(dot.contains("{ rank=sink; n255_0; }"));
+}
+
+#[test]
+fn genome_dot_ranks_neurons_by_height() {
+    let dot = sample_genome().to_dot();
+    let tree = cluster(&dot, 0);
+    assert!(tree.contains("{ rank=same; n0_1; n0_2; }"), "{}", tree);
+    // Single neurons at a height need no rank group
+    assert_eq!(dot.matches("rank=same").count(), 1);
+    assert!(tree.contains("n0_1 [label=\"hidden [0, 1]\\nbias 0.5\", shape=circle"));
+    assert!(tree.contains("n0_3 [label=\"hidden [0, 3]"));
+}
+
+#[test]
+fn genome_dot_keeps_detached_neurons_in_their_tree() {
+    let dot = sample_genome().to_dot();
+    let tree = cluster(&dot, 1);
+    assert!(tree.contains("n1_0 [label=\"input [1, 0]"), "{}", tree);
+    assert!(tree.contains("n1_5 [label=\"hidden [1, 5]"), "{}", tree);
+    assert!(edge(&dot, "n1_5", "n255_0").contains("color=\"#c53030\", style=solid"));
+}
+
+#[test]
+fn genome_dot_styles_edges_by_weight_and_status() {
+    let dot = sample_genome().to_dot();
+    assert!(edge(&dot, "n1_0", "n255_0").ends_with("penwidth=2.25, color=\"#999999\", style=dashed];"));
+    assert!(edge(&dot, "n0_0", "n0_1").ends_with("[label=\"2.000\", penwidth=4.00, color=\"#2b6cb0\", style=solid];"));
+    assert!(edge(&dot, "n0_1", "n0_3").contains("label=\"-1.000\", penwidth=2.25, color=\"#c53030\""));
+    assert!(edge(&dot, "n0_3", "n255_0").contains("penwidth=1.20"));
+    assert_eq!(dot.matches("style=dashed").count(), 1);
+    assert_eq!(dot.matches(" -> ").count(), 5);
+}
+
+#[test]
+fn network_dot_matches_the_genome_layout() {
+    let genome = sample_genome();
+    let mut network = Network::from_genome(genome.clone());
+    network.activate(&[1.0, 0.0]);
+    let dot = network.to_dot();
+    assert!(dot.starts_with("digraph network {"));
+    assert_eq!(dot.matches("subgraph cluster_tree_").count(), 2);
+    assert!(cluster(&dot, 0).contains("{ rank=same; n0_1; n0_2; }"));
+    assert!(cluster(&dot, 1).contains("n1_5 [label=\"hidden [1, 5]"));
+    let activation = network.neurons[&[0, 1]].activation;
+    assert!(dot.contains(&format!("hidden [0, 1]\\nbias 0.5\\nact {:.3}\"", activation)));
+    assert!(edge(&dot, "n1_0", "n255_0").contains("style=dashed"));
+
+    // Edges do not depend on the activations, so both renderings draw them the same way
+    let edges = |dot: &str| dot.lines().filter(|line| line.contains(" -> ")).map(str::to_string).collect::<Vec<_>>();
+    assert_eq!(edges(&dot), edges(&genome.to_dot()));
+}
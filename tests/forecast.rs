@@ -0,0 +1,19 @@
+use aster_ml::ForecastTask;
+
+#[test]
+fn scaling_is_fitted_on_the_training_segment_only() {
+    let mut series: Vec<f32> = (0..100).map(|t| (t % 10) as f32).collect();
+    series[90] = 1000.0;
+    let task = ForecastTask::new(series, 4, 1, 0.8);
+    assert_eq!(task.split, 80);
+    let train = &task.series[..task.split];
+    assert_eq!(train.iter().copied().fold(f32::INFINITY, f32::min), -1.0);
+    assert_eq!(train.iter().copied().fold(f32::NEG_INFINITY, f32::max), 1.0);
+    assert!(task.series[90] > 1.0);
+}
+
+#[test]
+#[should_panic(expected = "Window must be between 1 and 255")]
+fn window_cannot_reach_the_output_ids() {
+    ForecastTask::new(vec![0.0; 600], 256, 1, 0.5);
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use aster_ml::{convert_id_to_f32, get_tree_parent, Gene, Genome};
+
+fn synapse(from: [u8; 2], to: [u8; 2], enabled: bool) -> Gene {
+    Gene { id: from, flag: [10, if enabled { 10 } else { 11 }], local_data: convert_id_to_f32(to), extern_data: 0.5 }
+}
+
+fn child_count(genome: &Genome, id: [u8; 2]) -> u8 {
+    genome.genes.iter().find(|gene| gene.id == id && gene.flag[0] != 10).expect("neuron exists").flag[1]
+}
+
+fn has_neuron(genome: &Genome, id: [u8; 2]) -> bool {
+    genome.genes.iter().any(|gene| gene.id == id && gene.flag[0] != 10)
+}
+
+/// Every neuron's child count equals the hidden neurons below it, and every hidden neuron's parent exists
+fn assert_child_counts(genome: &Genome) {
+    for gene in genome.genes.iter().filter(|gene| gene.flag[0] != 10) {
+        let present = genome
+            .genes
+            .iter()
+            .filter(|other| other.flag[0] == 2 && get_tree_parent(other.id) == Some(gene.id))
+            .count();
+        assert_eq!(gene.flag[1] as usize, present, "child count of {:?}", gene.id);
+        if gene.flag[0] == 2 {
+            assert!(has_neuron(genome, get_tree_parent(gene.id).unwrap()), "parent of {:?}", gene.id);
+        }
+    }
+}
+
+/// One input with a hidden child [0, 1], connected along the tree and on to the output
+fn chain() -> Genome {
+    let mut genome = Genome::new(1, 1);
+    genome.genes[0].flag[1] = 1;
+    genome.genes.push(Gene { id: [0, 1], flag: [2, 0], local_data: 0.0, extern_data: 0.0 });
+    genome.genes.push(synapse([0, 0], [0, 1], false));
+    genome.genes.push(synapse([0, 1], [255, 0], true));
+    genome
+}
+
+#[test]
+fn adding_neurons_counts_children() {
+    let mut genome = Genome::new(2, 1);
+    for added in 1..=20 {
+        genome.random_child();
+        assert_eq!(genome.genes.iter().filter(|gene| gene.flag[0] == 2).count(), added);
+        assert_child_counts(&genome);
+    }
+    assert!(genome.genes.iter().filter(|gene| gene.flag[0] != 10).all(|gene| gene.flag[1] <= 2));
+}
+
+#[test]
+fn removing_a_cross_link_keeps_the_child_count() {
+    // The old bookkeeping lowered the source's count for any removed synapse
+    let mut genome = chain();
+    genome.genes.push(synapse([0, 0], [255, 0], false));
+    assert!(genome.remove_synapse([0, 0], [255, 0]));
+    assert_eq!(child_count(&genome, [0, 0]), 1);
+    assert_child_counts(&genome);
+}
+
+#[test]
+fn removing_an_isolated_neuron_frees_its_slot() {
+    let mut genome = chain();
+    genome.genes[0].flag[1] = 2;
+    genome.genes.push(Gene { id: [0, 2], flag: [2, 0], local_data: 0.0, extern_data: 0.0 });
+    genome.genes.push(synapse([0, 0], [0, 2], true));
+    assert!(genome.remove_synapse([0, 1], [255, 0]));
+    assert!(genome.remove_synapse([0, 0], [0, 1]));
+    assert!(!has_neuron(&genome, [0, 1]));
+    assert_eq!(child_count(&genome, [0, 0]), 1);
+    assert_child_counts(&genome);
+
+    // The first slot is free again while the second stays taken. The parent is picked at random,
+    // so grow until the input has been chosen.
+    while !has_neuron(&genome, [0, 1]) {
+        genome.random_child();
+        assert_child_counts(&genome);
+    }
+    assert_eq!(child_count(&genome, [0, 0]), 2);
+    assert_child_counts(&genome);
+}
+
+#[test]
+fn removing_a_self_loop_lowers_the_parent_count_once() {
+    // Source and destination are the same neuron, so it must only be removed and counted once
+    let mut genome = chain();
+    genome.genes[0].flag[1] = 2;
+    genome.genes.push(Gene { id: [0, 2], flag: [2, 0], local_data: 0.0, extern_data: 0.0 });
+    genome.genes.push(synapse([0, 2], [0, 2], false));
+    assert!(genome.remove_synapse([0, 2], [0, 2]));
+    assert!(!has_neuron(&genome, [0, 2]));
+    assert_eq!(child_count(&genome, [0, 0]), 1);
+    assert_child_counts(&genome);
+}
+
+#[test]
+fn neurons_with_children_are_not_removed() {
+    let mut genome = chain();
+    genome.genes[2].flag[1] = 1;
+    genome.genes.push(Gene { id: [0, 3], flag: [2, 0], local_data: 0.0, extern_data: 0.0 });
+    genome.genes.push(synapse([0, 3], [255, 0], true));
+    assert!(genome.remove_synapse([0, 1], [255, 0]));
+    assert!(genome.remove_synapse([0, 0], [0, 1]));
+    assert!(has_neuron(&genome, [0, 1]));
+    assert_child_counts(&genome);
+}
+
+#[test]
+fn mutations_keep_child_counts_in_step() {
+    for seed in 0..40 {
+        assert_child_counts(&common::churned_genome(seed, 3, 2, 80));
+    }
+}
@@ -0,0 +1,131 @@
+mod common;
+
+use aster_ml::{play_predator_prey, Genome, Network, PredatorPreyConfig, SnakeConfig, SnakeGame, Turn};
+
+/// Network that always picks `action`: no synapses, and only that output has a positive bias
+fn fixed_action(inputs: u16, outputs: u16, action: usize) -> Network {
+    let mut genome = Genome::new(inputs, outputs);
+    for gene in genome.genes.iter_mut().filter(|gene| gene.flag[0] == 3) {
+        gene.local_data = if 255 - gene.id[0] as usize == action { 1.0 } else { -1.0 };
+    }
+    Network::from_genome(genome)
+}
+
+fn snake_game() -> SnakeGame {
+    SnakeGame::new(SnakeConfig { seed: 9, ..SnakeConfig::default() })
+}
+
+#[test]
+fn snake_episodes_replay_from_the_seed() {
+    let config = SnakeConfig { seed: 4, ..SnakeConfig::default() };
+    for seed in 0..5 {
+        let genome = common::random_genome(seed, config.input_count(), config.output_count(), 20);
+        let first = SnakeGame::play(&config, &mut Network::from_genome(genome.clone()));
+        let second = SnakeGame::play(&config, &mut Network::from_genome(genome));
+        assert_eq!(first.actions, second.actions);
+        assert_eq!((first.food_eaten, first.steps, first.died), (second.food_eaten, second.steps, second.died));
+        assert_eq!(first.score.to_bits(), second.score.to_bits());
+
+        let frames = SnakeGame::replay(&config, &first.actions);
+        assert_eq!(frames.len(), first.actions.len() + 1);
+        let again = SnakeGame::replay(&config, &first.actions);
+        for (frame, other) in frames.iter().zip(&again) {
+            assert_eq!(frame.render(), other.render());
+        }
+    }
+}
+
+#[test]
+fn snake_dies_running_into_itself() {
+    let mut game = snake_game();
+    game.state.body = vec![(5, 5), (4, 5), (4, 6), (5, 6), (6, 6)];
+    game.state.heading = 1;
+    game.state.food = (0, 0);
+    assert!(!game.step(Turn::Right));
+    assert!(!game.alive);
+    assert_eq!(game.state.body.len(), 5);
+}
+
+#[test]
+fn snake_may_follow_its_own_tail() {
+    let mut game = snake_game();
+    game.state.body = vec![(5, 5), (5, 6), (6, 6), (6, 5)];
+    game.state.heading = 1;
+    game.state.food = (0, 0);
+    assert!(game.step(Turn::Straight));
+    assert_eq!(game.state.body, vec![(6, 5), (5, 5), (5, 6), (6, 6)]);
+}
+
+#[test]
+fn snake_grows_when_eating() {
+    let mut game = snake_game();
+    let head = game.state.body[0];
+    game.state.food = (head.0 + 1, head.1);
+    assert!(game.step(Turn::Straight));
+    assert_eq!(game.food_eaten, 1);
+    assert_eq!(game.state.body.len(), 4);
+    assert_eq!(game.state.body[0], (head.0 + 1, head.1));
+    assert!(!game.state.body.contains(&game.state.food));
+}
+
+#[test]
+fn predator_prey_replays_from_the_seed() {
+    let config = PredatorPreyConfig::default();
+    let genomes: Vec<Genome> = (0..config.roles().len() as u64)
+        .map(|seed| common::random_genome(seed, config.input_count(), config.output_count(), 15))
+        .collect();
+    let networks = || -> Vec<Network> { genomes.iter().cloned().map(Network::from_genome).collect() };
+    let first = play_predator_prey(&config, &mut networks());
+    let second = play_predator_prey(&config, &mut networks());
+    assert_eq!(first.frames, second.frames);
+    assert_eq!(first.scores, second.scores);
+}
+
+/// On a single row, finds a seed where the prey starts next to the predator with room for the
+/// predator to step away, and returns it with the direction from prey to predator (+1 or -1)
+fn adjacent_start(config: &PredatorPreyConfig) -> (u64, i32) {
+    for seed in 0..100 {
+        let config = PredatorPreyConfig { seed, ..config.clone() };
+        let stay = || fixed_action(config.input_count(), config.output_count(), 0);
+        let start = &play_predator_prey(&config, &mut [stay(), stay()]).frames[0];
+        let (predator, prey) = (start[0].unwrap().0, start[1].unwrap().0);
+        let direction = predator - prey;
+        if direction.abs() == 1 && (0..config.width).contains(&(predator + direction)) {
+            return (seed, direction);
+        }
+    }
+    panic!("no seed puts the agents next to each other");
+}
+
+#[test]
+fn prey_moving_onto_a_predator_is_caught() {
+    let config = PredatorPreyConfig { width: 4, height: 1, predators: 1, prey: 1, max_steps: 5, seed: 0 };
+    let (seed, direction) = adjacent_start(&config);
+    let config = PredatorPreyConfig { seed, ..config };
+    // Actions: 2 moves right, 4 moves left. Both step the same way, so the predator leaves the cell
+    // the prey moves into.
+    let action = if direction > 0 { 2 } else { 4 };
+    let mut networks = [
+        fixed_action(config.input_count(), config.output_count(), action),
+        fixed_action(config.input_count(), config.output_count(), action),
+    ];
+    let outcome = play_predator_prey(&config, &mut networks);
+    assert_eq!(outcome.steps, 1);
+    assert_eq!(outcome.scores[0], 1.0);
+    assert_eq!(outcome.frames[1][1], None);
+}
+
+#[test]
+fn predator_moving_onto_prey_catches_it() {
+    let config = PredatorPreyConfig { width: 4, height: 1, predators: 1, prey: 1, max_steps: 5, seed: 0 };
+    let (seed, direction) = adjacent_start(&config);
+    let config = PredatorPreyConfig { seed, ..config };
+    let toward_prey = if direction > 0 { 4 } else { 2 };
+    let mut networks = [
+        fixed_action(config.input_count(), config.output_count(), toward_prey),
+        fixed_action(config.input_count(), config.output_count(), 0),
+    ];
+    let outcome = play_predator_prey(&config, &mut networks);
+    assert_eq!(outcome.steps, 1);
+    assert_eq!(outcome.scores, vec![1.0, 0.0]);
+}
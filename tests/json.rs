@@ -0,0 +1,96 @@
+mod common;
+
+use aster_ml::{GenerationSummary, Genome, GenomeError, JsonError, JsonValue, Network, JSON_MAX_DEPTH};
+
+#[test]
+fn genomes_round_trip() {
+    for seed in 0..10 {
+        let genome = common::churned_genome(seed, 3, 2, 40);
+        let json = genome.to_json().unwrap();
+        let parsed = Genome::from_json(&json).unwrap();
+        common::assert_same_genes(&genome, &parsed);
+        assert_eq!(parsed.to_json().unwrap(), json);
+    }
+}
+
+#[test]
+fn networks_round_trip_with_activations() {
+    for seed in 0..10 {
+        let mut network = Network::from_genome(common::random_recurrent_genome(seed, 3, 2, 30));
+        network.activate(&[0.5, -0.25, 1.0]);
+        network.activate(&[0.1, 0.2, 0.3]);
+        let json = network.to_json().unwrap();
+        let mut parsed = Network::from_json(&json).unwrap();
+        assert_eq!(parsed.to_json().unwrap(), json);
+        // Restored state carries into the next step
+        let bits = |outputs: Vec<f32>| outputs.iter().map(|output| output.to_bits()).collect::<Vec<u32>>();
+        assert_eq!(bits(parsed.activate(&[1.0, 0.0, -1.0])), bits(network.activate(&[1.0, 0.0, -1.0])));
+    }
+}
+
+#[test]
+fn run_summaries_round_trip_with_non_finite_values_as_null() {
+    let population: Vec<Genome> = (0..6).map(|seed| common::random_genome(seed, 2, 1, 15)).collect();
+    let mut summaries = vec![
+        GenerationSummary::new(0, &population, &[0.5, 1.25, -3.0, 2.0, 0.0, 1.0]),
+        GenerationSummary::new(1, &population, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]),
+    ];
+    summaries[1].species_sizes = vec![4, 2];
+    summaries[1].fitness_std_dev = f32::INFINITY;
+    summaries[1].mean_fitness = f32::NAN;
+
+    let json = GenerationSummary::run_to_json(&summaries).unwrap();
+    assert!(json.contains("\"fitness_std_dev\": null"), "{}", json);
+    let parsed = GenerationSummary::run_from_json(&json).unwrap();
+    assert_eq!(parsed.len(), 2);
+    for (summary, parsed) in summaries.iter().zip(&parsed) {
+        assert_eq!(parsed.generation, summary.generation);
+        assert_eq!(parsed.population_size, summary.population_size);
+        assert_eq!(parsed.species_sizes, summary.species_sizes);
+        assert_eq!(parsed.best_fitness.to_bits(), summary.best_fitness.to_bits());
+        assert_eq!(parsed.median_fitness.to_bits(), summary.median_fitness.to_bits());
+        assert_eq!(parsed.mean_synapses.to_bits(), summary.mean_synapses.to_bits());
+        common::assert_same_genes(&summary.champion, &parsed.champion);
+    }
+    assert!(parsed[1].fitness_std_dev.is_nan());
+    assert!(parsed[1].mean_fitness.is_nan());
+    assert_eq!(GenerationSummary::run_to_json(&parsed).unwrap(), json);
+}
+
+#[test]
+fn rejects_deeply_nested_documents() {
+    let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+    assert!(nested(JSON_MAX_DEPTH).parse::<JsonValue>().is_ok());
+    match nested(JSON_MAX_DEPTH + 1).parse::<JsonValue>() {
+        Err(JsonError::Syntax { line: 1, column, .. }) => assert_eq!(column, JSON_MAX_DEPTH + 1),
+        other => panic!("expected a syntax error, got {:?}", other),
+    }
+    // Far deeper than the stack could recurse, and never closed
+    assert!("[{\"a\":".repeat(100_000).parse::<JsonValue>().is_err());
+}
+
+#[test]
+fn unknown_gene_types_and_statuses_are_not_exported() {
+    let mut genome = Genome::new(2, 1);
+    genome.genes[1].flag[0] = 7;
+    assert!(matches!(genome.to_json(), Err(GenomeError::UnknownGeneType { gene: 1, flag: 7 })));
+
+    let mut genome = common::random_genome(3, 2, 1, 10);
+    let synapse = genome.genes.iter().position(|gene| gene.flag[0] == 10).unwrap();
+    genome.genes[synapse].flag[1] = 12;
+    assert!(matches!(genome.to_json(), Err(GenomeError::UnknownSynapseStatus { gene, flag: 12 }) if gene == synapse));
+    let network = Network::from_genome(genome.clone());
+    assert!(matches!(network.to_json(), Err(GenomeError::UnknownSynapseStatus { flag: 12, .. })));
+    let summary = GenerationSummary::new(0, &[genome], &[1.0]);
+    assert!(GenerationSummary::run_to_json(&[summary]).is_err());
+}
+
+#[test]
+fn numbers_follow_the_json_grammar() {
+    for valid in ["0", "-0", "12", "-7", "0.5", "-0.25", "1e5", "1E+5", "2.5e-3", "10.0"] {
+        assert_eq!(valid.parse::<JsonValue>().unwrap(), JsonValue::Number(valid.to_string()), "{}", valid);
+    }
+    for invalid in ["1.", "01", "-01", ".5", "-.5", "+1", "-", "1e", "1e+", "1.e3", "0x10", "1.5.2", "--1", "1-2", "[1.]"] {
+        assert!(matches!(invalid.parse::<JsonValue>(), Err(JsonError::Syntax { .. })), "{} was accepted", invalid);
+    }
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use aster_ml::{Genealogy, Genome, MutationOperator};
+
+#[test]
+fn ids_link_parents_and_children() {
+    let founder_a = common::random_genome(1, 2, 1, 10);
+    let founder_b = common::random_genome(2, 2, 1, 10);
+    assert_ne!(founder_a.lineage.id, founder_b.lineage.id);
+    assert!(founder_a.lineage.parents.is_empty());
+
+    // Mutated offspring
+    let mut child = founder_a.offspring(1);
+    assert_ne!(child.lineage.id, founder_a.lineage.id);
+    assert_eq!(child.lineage.parents, vec![founder_a.lineage.id]);
+    assert_eq!(child.lineage.birth_generation, 1);
+    assert!(child.lineage.mutations.is_empty());
+    // Both always apply: a new neuron comes with an enabled synapse from its parent
+    child.random_child();
+    child.disable_random_synapse();
+    assert_eq!(child.lineage.mutations, vec![MutationOperator::AddNeuron, MutationOperator::DisableSynapse]);
+
+    // Crossover child of the mutated offspring and the second founder
+    let mut crossed = child.clone();
+    crossed.record_birth(&[&child, &founder_b], 2);
+    assert_eq!(crossed.lineage.parents, vec![child.lineage.id, founder_b.lineage.id]);
+    assert!(crossed.lineage.mutations.is_empty());
+
+    let mut genealogy = Genealogy::new();
+    genealogy.record_generation(0, &[founder_a.clone(), founder_b.clone()], &[1.0, 2.0]);
+    genealogy.record_generation(1, &[child.clone()], &[3.0]);
+    genealogy.record_generation(2, &[crossed.clone()], &[4.0]);
+    let ancestry: Vec<u64> = genealogy.ancestry(crossed.lineage.id).iter().map(|entry| entry.id).collect();
+    let mut founders = [founder_a.lineage.id, founder_b.lineage.id];
+    founders.sort();
+    assert_eq!(ancestry, [&founders[..], &[child.lineage.id, crossed.lineage.id]].concat());
+    assert_eq!(genealogy.entries[&child.lineage.id].mutations.len(), 2);
+}
+
+#[test]
+fn imported_ids_are_not_handed_out_again() {
+    let mut genome = Genome::new(2, 1);
+    genome.lineage.id += 1_000_000;
+    let imported = Genome::from_json(&genome.to_json().unwrap()).unwrap();
+    assert_eq!(imported.lineage.id, genome.lineage.id);
+    assert!(Genome::new(2, 1).lineage.id > imported.lineage.id);
+    assert!(imported.offspring(1).lineage.id > imported.lineage.id);
+
+    let mut genealogy = Genealogy::new();
+    let mut logged = Genome::new(2, 1);
+    logged.lineage.id += 2_000_000;
+    genealogy.record_generation(0, &[logged.clone()], &[1.0]);
+    Genealogy::from_json(&genealogy.to_json()).unwrap();
+    assert!(Genome::new(2, 1).lineage.id > logged.lineage.id);
+}
+
+#[test]
+fn formats_without_lineage_start_a_new_root() {
+    let genome = common::random_genome(3, 2, 1, 10).offspring(4);
+    for decoded in [
+        Genome::from_bytes(&genome.to_bytes()).unwrap(),
+        Genome::from_code(&genome.to_code()).unwrap(),
+        genome.to_text().unwrap().parse().unwrap(),
+    ] {
+        assert_ne!(decoded.lineage.id, genome.lineage.id);
+        assert!(decoded.lineage.parents.is_empty());
+        assert_eq!(decoded.lineage.birth_generation, 0);
+    }
+}
@@ -0,0 +1,23 @@
+use aster_ml::{Maze, MazeError};
+
+#[test]
+fn blank_rows_and_short_rows_are_open_floor() {
+    let maze: Maze = "\n#####\n#S..\n#...#\n\n#  G#\n#####\n\n".parse().unwrap();
+    assert_eq!((maze.width, maze.height), (5, 6));
+    assert_eq!(maze.goal, (3.5, 1.5));
+    // Padding at the end of row 1, the blank row 3 and the spaces in row 4 are open
+    assert!(!maze.collides((4.5, 4.5), 0.3));
+    assert!(!maze.collides((2.5, 2.5), 0.3));
+    assert!(!maze.collides((1.5, 1.5), 0.3));
+    // The map stays closed past its widest row
+    assert!(maze.collides((4.9, 4.5), 0.3));
+}
+
+#[test]
+fn errors_report_lines_of_the_original_text() {
+    match "\n\n#####\n#S?G#\n#####".parse::<Maze>() {
+        Err(MazeError::UnknownCell { line, column, found }) => assert_eq!((line, column, found), (4, 3, '?')),
+        other => panic!("expected an unknown cell error, got {:?}", other),
+    }
+    assert!(matches!(" \n\t\n".parse::<Maze>(), Err(MazeError::Empty)));
+}
@@ -0,0 +1,39 @@
+use aster_ml::{log_loss, roc_auc, ConfusionMatrix, CsvOptions, Dataset, DatasetError, Genome, MetricsReport, Network};
+
+#[test]
+fn predictions_wider_than_targets_get_their_own_column() {
+    let targets = vec![vec![1.0, 0.0], vec![0.0, 1.0]];
+    let predictions = vec![vec![0.9, 0.1, -0.5], vec![0.0, 0.2, 0.8]];
+    let confusion = ConfusionMatrix::new(&predictions, &targets);
+    assert_eq!(confusion.counts, [[1, 0, 0], [0, 0, 1], [0, 0, 0]]);
+    assert_eq!(confusion.accuracy(), 0.5);
+}
+
+#[test]
+fn report_on_a_mismatched_network_is_an_error() {
+    let dataset = Dataset::parse_csv("1,2,a\n3,4,b\n", &CsvOptions::default()).unwrap();
+    let mut network = Network::from_genome(Genome::new(3, 2));
+    let report = MetricsReport::new(&mut network, &dataset);
+    assert!(matches!(report, Err(DatasetError::ShapeMismatch { inputs: 3, expected_inputs: 2, .. })));
+
+    let mut network = dataset.new_network();
+    let report = MetricsReport::new(&mut network, &dataset).unwrap();
+    assert_eq!(report.class_names, ["a", "b"]);
+    assert_eq!(report.confusion.total(), 2);
+}
+
+#[test]
+fn target_class_without_an_output_has_probability_zero() {
+    let targets = vec![vec![0.0, 0.0, 1.0], vec![1.0, 0.0, 0.0]];
+    let predictions = vec![vec![0.5, -0.5], vec![0.5]];
+    let expected = (-(1e-7f32).ln() - 0.25f32.ln()) / 2.0;
+    assert!((log_loss(&predictions, &targets) - expected).abs() < 1e-4);
+}
+
+#[test]
+fn roc_auc_skips_rows_without_outputs() {
+    let targets = vec![vec![0.0, 1.0], vec![1.0, 0.0], vec![0.0, 1.0], vec![1.0, 0.0]];
+    let predictions = vec![vec![0.8], vec![], vec![0.1], vec![-0.4]];
+    assert_eq!(roc_auc(&predictions, &targets), 1.0);
+    assert_eq!(roc_auc(&[vec![]], &[vec![0.0, 1.0]]), 0.5);
+}
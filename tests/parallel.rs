@@ -0,0 +1,88 @@
+mod common;
+
+use aster_ml::{CompiledNetwork, Genome, Network, ParallelEvaluator};
+use rand::prelude::*;
+use rand::rngs::StdRng;
+
+fn population() -> Vec<Genome> {
+    (0..24)
+        .map(|seed| match seed % 2 {
+            0 => common::random_genome(seed, 3, 2, 25),
+            _ => common::random_recurrent_genome(seed, 3, 2, 25),
+        })
+        .collect()
+}
+
+fn rows(seed: u64, count: usize) -> Vec<Vec<f32>> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..count).map(|_| (0..3).map(|_| rng.gen_range(-1.0..1.0)).collect()).collect()
+}
+
+fn bits(values: &[f32]) -> Vec<u32> {
+    values.iter().map(|value| value.to_bits()).collect()
+}
+
+#[test]
+fn fitness_does_not_depend_on_worker_count() {
+    let population = population();
+    // Sums outputs over a random input sequence, so both the rng and carried state feed the score
+    let fitness = |genome: &Genome, rng: &mut StdRng| {
+        let mut network = Network::from_genome(genome.clone());
+        (0..8)
+            .map(|_| {
+                let inputs: Vec<f32> = (0..3).map(|_| rng.gen_range(-1.0..1.0)).collect();
+                network.activate(&inputs).iter().sum::<f32>()
+            })
+            .sum()
+    };
+
+    let expected = bits(&ParallelEvaluator::new(1, 42).evaluate(3, &population, fitness));
+    for workers in [2, 5, 0] {
+        let scores = ParallelEvaluator::new(workers, 42).evaluate(3, &population, fitness);
+        assert_eq!(bits(&scores), expected, "{} workers", workers);
+    }
+    let reseeded = ParallelEvaluator::new(1, 43).evaluate(3, &population, fitness);
+    assert_ne!(bits(&reseeded), expected);
+}
+
+#[test]
+fn single_batch_and_compiled_activation_agree() {
+    let rows = rows(7, 16);
+    for genome in population() {
+        let mut network = Network::from_genome(genome.clone());
+        let mut compiled = CompiledNetwork::from_genome(&genome);
+        let batch = network.activate_batch(&rows);
+        let compiled_batch = compiled.activate_batch(&rows);
+        for (row, (batched, compiled_batched)) in rows.iter().zip(batch.iter().zip(&compiled_batch)) {
+            network.reset();
+            compiled.reset();
+            let single = bits(&network.activate(row));
+            assert_eq!(bits(batched), single);
+            assert_eq!(bits(compiled_batched), single);
+            assert_eq!(bits(&compiled.activate(row)), single);
+        }
+
+        // Carried state across steps, which matters for the recurrent genomes
+        network.reset();
+        compiled.reset();
+        for row in &rows {
+            assert_eq!(bits(&compiled.activate(row)), bits(&network.activate(row)));
+        }
+    }
+}
+
+#[test]
+fn compiled_networks_start_from_stored_activations() {
+    let rows = rows(11, 6);
+    let mut rng = StdRng::seed_from_u64(5);
+    for mut genome in population() {
+        for gene in genome.genes.iter_mut().filter(|gene| gene.flag[0] != 10) {
+            gene.extern_data = rng.gen_range(-1.0..1.0);
+        }
+        let mut network = Network::from_genome(genome.clone());
+        let mut compiled = CompiledNetwork::from_genome(&genome);
+        for row in &rows {
+            assert_eq!(bits(&compiled.activate(row)), bits(&network.activate(row)));
+        }
+    }
+}
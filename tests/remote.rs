@@ -0,0 +1,185 @@
+mod common;
+
+use std::io::{BufRead, BufReader, Read};
+use std::net::{TcpListener, TcpStream};
+use std::process::{Child, Command};
+use std::thread;
+use std::time::Duration;
+
+use aster_ml::remote::{RemoteEvaluator, WorkerError, WorkerSpec};
+use aster_ml::{Genome, Network};
+
+const WORKER: &str = env!("CARGO_BIN_EXE_aster_worker");
+
+const XOR: [([f32; 2], f32); 4] = [([0.0, 0.0], -1.0), ([0.0, 1.0], 1.0), ([1.0, 0.0], 1.0), ([1.0, 1.0], -1.0)];
+
+/// The score `aster_worker` computes, evaluated locally
+fn xor_fitness(genome: &Genome) -> f32 {
+    let mut network = Network::from_genome(genome.clone());
+    let squared_error: f32 = XOR
+        .iter()
+        .map(|(inputs, target)| {
+            network.reset();
+            (network.activate(inputs)[0] - target).powi(2)
+        })
+        .sum();
+    4.0 - squared_error
+}
+
+fn process_worker(args: &[&str], timeout_ms: u64, retries: usize) -> RemoteEvaluator {
+    let mut evaluator = RemoteEvaluator::new(WorkerSpec::Process {
+        program: WORKER.into(),
+        args: args.iter().map(|arg| arg.to_string()).collect(),
+    });
+    evaluator.timeout = Duration::from_millis(timeout_ms);
+    evaluator.retries = retries;
+    evaluator
+}
+
+/// Kills a listening worker when the test ends, even on failure
+struct Listener(Child);
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// Waits until a listening worker accepts connections and greets them
+fn wait_for_worker<S: Read, F: Fn() -> std::io::Result<S>>(connect: F) {
+    for _ in 0..100 {
+        if let Ok(stream) = connect() {
+            let mut line = String::new();
+            BufReader::new(stream).read_line(&mut line).unwrap();
+            assert_eq!(line.trim(), "READY");
+            return;
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+    panic!("worker did not start listening");
+}
+
+#[test]
+fn evaluates_genomes_and_episodes() {
+    let genome = common::random_genome(3, 2, 1, 20);
+    let expected = xor_fitness(&genome);
+    let mut evaluator = process_worker(&[], 5000, 0);
+    assert_eq!(evaluator.evaluate_genome(&genome).unwrap(), expected);
+    let mut network = Network::from_genome(genome);
+    assert_eq!(evaluator.evaluate_streaming(&mut network).unwrap(), expected);
+}
+
+#[test]
+fn worker_errors_are_not_retried() {
+    let mut evaluator = process_worker(&[], 5000, 2);
+    let err = evaluator.evaluate_genome(&Genome::new(3, 1)).unwrap_err();
+    assert!(matches!(err, WorkerError::Worker(_)), "{}", err);
+}
+
+#[test]
+fn hung_worker_times_out() {
+    let mut evaluator = process_worker(&["--delay-ms", "5000"], 100, 1);
+    match evaluator.evaluate_genome(&Genome::new(2, 1)).unwrap_err() {
+        WorkerError::RetriesExhausted { attempts, last } => {
+            assert_eq!(attempts, 2);
+            assert!(matches!(*last, WorkerError::Timeout), "{}", last);
+        }
+        err => panic!("expected exhausted retries, got {}", err),
+    }
+}
+
+#[test]
+fn crashed_worker_fails_without_retries() {
+    let genome = common::random_genome(5, 2, 1, 20);
+    let mut evaluator = process_worker(&["--exit-after", "1"], 5000, 0);
+    assert_eq!(evaluator.evaluate_genome(&genome).unwrap(), xor_fitness(&genome));
+    match evaluator.evaluate_genome(&genome).unwrap_err() {
+        WorkerError::RetriesExhausted { attempts, last } => {
+            assert_eq!(attempts, 1);
+            assert!(matches!(*last, WorkerError::Crashed), "{}", last);
+        }
+        err => panic!("expected exhausted retries, got {}", err),
+    }
+}
+
+#[test]
+fn crashed_worker_is_restarted_on_retry() {
+    let genome = common::random_genome(5, 2, 1, 20);
+    let mut evaluator = process_worker(&["--exit-after", "1"], 5000, 1);
+    for _ in 0..3 {
+        assert_eq!(evaluator.evaluate_genome(&genome).unwrap(), xor_fitness(&genome));
+    }
+}
+
+/// The worker serves one connection at a time, so a reconnect only gets READY once the
+/// dropped connection was really closed
+#[test]
+fn dropped_tcp_connection_is_closed() {
+    let address = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().to_string();
+    let _worker = Listener(Command::new(WORKER).args(["--listen", &format!("tcp:{}", address)]).spawn().unwrap());
+    wait_for_worker(|| TcpStream::connect(&address));
+
+    let genome = common::random_genome(7, 2, 1, 20);
+    let mut evaluator = RemoteEvaluator::new(WorkerSpec::Tcp(address));
+    evaluator.timeout = Duration::from_secs(2);
+    evaluator.retries = 0;
+    // An ERROR reply drops the connection
+    assert!(matches!(evaluator.evaluate_genome(&Genome::new(3, 1)), Err(WorkerError::Worker(_))));
+    assert_eq!(evaluator.evaluate_genome(&genome).unwrap(), xor_fitness(&genome));
+}
+
+#[cfg(unix)]
+#[test]
+fn dropped_unix_connection_is_closed() {
+    let path = std::env::temp_dir().join(format!("aster_worker_test_{}.sock", std::process::id()));
+    let _worker = Listener(Command::new(WORKER).args(["--listen", &format!("unix:{}", path.display())]).spawn().unwrap());
+    wait_for_worker(|| std::os::unix::net::UnixStream::connect(&path));
+
+    let genome = common::random_genome(7, 2, 1, 20);
+    let mut evaluator = RemoteEvaluator::new(WorkerSpec::Unix(path.clone()));
+    evaluator.timeout = Duration::from_secs(2);
+    evaluator.retries = 0;
+    assert!(matches!(evaluator.evaluate_genome(&Genome::new(3, 1)), Err(WorkerError::Worker(_))));
+    assert_eq!(evaluator.evaluate_genome(&genome).unwrap(), xor_fitness(&genome));
+    drop(evaluator);
+    let _ = std::fs::remove_file(path);
+}
+
+/// The first request times out and the evaluator hangs up while the worker is still busy with it.
+/// The worker's reply then hits a closed socket, and it must keep serving the next connection.
+#[test]
+fn tcp_worker_survives_a_timed_out_request() {
+    let address = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().to_string();
+    let args = ["--listen", &format!("tcp:{}", address), "--delay-ms", "300"];
+    let _worker = Listener(Command::new(WORKER).args(args).spawn().unwrap());
+    wait_for_worker(|| TcpStream::connect(&address));
+
+    let genome = common::random_genome(9, 2, 1, 20);
+    let mut evaluator = RemoteEvaluator::new(WorkerSpec::Tcp(address));
+    evaluator.timeout = Duration::from_millis(50);
+    evaluator.retries = 0;
+    match evaluator.evaluate_genome(&genome).unwrap_err() {
+        WorkerError::RetriesExhausted { last, .. } => assert!(matches!(*last, WorkerError::Timeout), "{}", last),
+        err => panic!("expected exhausted retries, got {}", err),
+    }
+    evaluator.timeout = Duration::from_secs(5);
+    for _ in 0..2 {
+        assert_eq!(evaluator.evaluate_genome(&genome).unwrap(), xor_fitness(&genome));
+    }
+}
+
+/// A worker that answers one request and then ignores QUIT
+#[cfg(unix)]
+#[test]
+fn shutdown_kills_a_worker_that_does_not_quit() {
+    let mut evaluator = RemoteEvaluator::new(WorkerSpec::Process {
+        program: "sh".into(),
+        args: vec!["-c".into(), "echo READY; read line; echo FITNESS 1; exec sleep 30".into()],
+    });
+    evaluator.timeout = Duration::from_millis(200);
+    assert_eq!(evaluator.evaluate_genome(&Genome::new(2, 1)).unwrap(), 1.0);
+    let start = std::time::Instant::now();
+    evaluator.shutdown();
+    assert!(start.elapsed() < Duration::from_secs(5), "{:?}", start.elapsed());
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use aster_ml::{EvolutionReplay, JsonValue, PlotFormat, VisualizeOptions};
+
+fn frame_count(figure: &JsonValue) -> usize {
+    frame_names(figure).len()
+}
+
+fn text(value: Option<&JsonValue>) -> String {
+    match value {
+        Some(JsonValue::String(text)) => text.clone(),
+        other => panic!("expected a string, got {:?}", other),
+    }
+}
+
+fn frame_names(figure: &JsonValue) -> Vec<String> {
+    match figure.get("frames") {
+        Some(JsonValue::Array(frames)) => frames.iter().map(|frame| text(frame.get("name"))).collect(),
+        other => panic!("expected frames, got {:?}", other),
+    }
+}
+
+/// Label and target frame of each slider step
+fn slider_steps(figure: &JsonValue) -> Vec<(String, String)> {
+    let sliders = figure.get("layout").and_then(|layout| layout.get("sliders"));
+    let Some(JsonValue::Array(sliders)) = sliders else { panic!("expected sliders, got {:?}", sliders) };
+    let Some(JsonValue::Array(steps)) = sliders[0].get("steps") else { panic!("expected slider steps") };
+    steps
+        .iter()
+        .map(|step| {
+            let Some(JsonValue::Array(args)) = step.get("args") else { panic!("expected step args") };
+            let JsonValue::Array(frames) = &args[0] else { panic!("expected a frame list") };
+            (text(step.get("label")), text(frames.first()))
+        })
+        .collect()
+}
+
+#[test]
+fn empty_replay_renders_an_empty_figure() {
+    let replay = EvolutionReplay::new();
+    let figure = replay.to_figure(&VisualizeOptions::default());
+    assert_eq!(figure.get("data"), Some(&JsonValue::Array(Vec::new())));
+    assert_eq!(frame_count(&figure), 0);
+    assert!(replay.render(&VisualizeOptions::default(), PlotFormat::Html).contains("Plotly.newPlot"));
+}
+
+#[test]
+fn fades_between_recorded_generations() {
+    let mut replay = EvolutionReplay::new();
+    for generation in 0..3 {
+        replay.record(generation, &common::random_genome(generation as u64, 2, 1, 5 * (generation + 1)));
+    }
+    let figure = replay.to_figure(&VisualizeOptions::default());
+    assert_eq!(frame_count(&figure), 3 + 2 * replay.fade_steps);
+    let parsed: JsonValue = figure.to_string().parse().unwrap();
+    assert_eq!(parsed, figure);
+}
+
+#[test]
+fn repeated_generations_keep_their_own_frames() {
+    let mut replay = EvolutionReplay::new();
+    replay.fade_steps = 1;
+    for (generation, seed) in [(0, 1), (5, 2), (5, 3)] {
+        replay.record(generation, &common::random_genome(seed, 2, 1, 10));
+    }
+    let figure = replay.to_figure(&VisualizeOptions::default());
+    assert_eq!(frame_names(&figure), ["0", "1.1", "1", "2.1", "2"]);
+    let steps = slider_steps(&figure);
+    let expected = [("0", "0"), ("5", "1"), ("5", "2")].map(|(label, frame)| (label.to_string(), frame.to_string()));
+    assert_eq!(steps, expected);
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use aster_ml::{complexity_plot, fitness_plot, species_plot, write_run_plots, GenerationSummary, Genome, JsonValue};
+
+fn summaries() -> Vec<GenerationSummary> {
+    let population: Vec<Genome> = (0..4).map(|seed| common::random_genome(seed, 2, 1, 10)).collect();
+    let mut summaries: Vec<GenerationSummary> = (0..3)
+        .map(|generation| {
+            let fitness: Vec<f32> = (0..4).map(|index| (generation + index) as f32).collect();
+            GenerationSummary::new(generation, &population, &fitness)
+        })
+        .collect();
+    summaries[0].species_sizes = vec![4];
+    summaries[1].species_sizes = vec![1, 3];
+    summaries[2].species_sizes = vec![2, 0, 1, 1];
+    summaries
+}
+
+fn traces(json: &str) -> Vec<JsonValue> {
+    let figure: JsonValue = json.parse().unwrap();
+    match figure.get("data") {
+        Some(JsonValue::Array(traces)) => traces.clone(),
+        other => panic!("expected a data array, got {:?}", other),
+    }
+}
+
+fn numbers(trace: &JsonValue, axis: &str) -> Vec<f64> {
+    match trace.get(axis) {
+        Some(JsonValue::Array(values)) => values
+            .iter()
+            .map(|value| match value {
+                JsonValue::Number(number) => number.parse().unwrap(),
+                other => panic!("expected a number, got {}", other),
+            })
+            .collect(),
+        other => panic!("expected an array, got {:?}", other),
+    }
+}
+
+fn name(trace: &JsonValue) -> String {
+    match trace.get("name") {
+        Some(JsonValue::String(name)) => name.clone(),
+        other => panic!("expected a name, got {:?}", other),
+    }
+}
+
+#[test]
+fn species_missing_from_a_generation_count_as_empty() {
+    let traces = traces(&species_plot(&summaries()).to_json());
+    let names: Vec<String> = traces.iter().map(name).collect();
+    assert_eq!(names, ["Species 0", "Species 1", "Species 2", "Species 3"]);
+    let sizes: Vec<Vec<f64>> = traces.iter().map(|trace| numbers(trace, "y")).collect();
+    assert_eq!(sizes, [vec![4.0, 1.0, 2.0], vec![0.0, 3.0, 0.0], vec![0.0, 0.0, 1.0], vec![0.0, 0.0, 1.0]]);
+    for trace in &traces {
+        assert_eq!(numbers(trace, "x"), [0.0, 1.0, 2.0]);
+        assert_eq!(trace.get("stackgroup"), Some(&JsonValue::String("species".to_string())));
+    }
+}
+
+#[test]
+fn fitness_and_complexity_follow_the_summaries() {
+    let summaries = summaries();
+    let fitness = traces(&fitness_plot(&summaries).to_json());
+    let best = fitness.iter().find(|trace| name(trace) == "Best").unwrap();
+    assert_eq!(numbers(best, "y"), [3.0, 4.0, 5.0]);
+    // Two traces per band, then best, median and mean
+    assert_eq!(fitness.len(), 7);
+
+    let complexity = traces(&complexity_plot(&summaries).to_json());
+    let synapses = complexity.iter().find(|trace| name(trace) == "Champion synapses").unwrap();
+    let champion_synapses = summaries[0].champion.genes.iter().filter(|gene| gene.flag[0] == 10).count() as f64;
+    assert_eq!(numbers(synapses, "y")[0], champion_synapses);
+}
+
+#[test]
+fn empty_runs_plot_without_data() {
+    assert_eq!(traces(&fitness_plot(&[]).to_json()).len(), 7);
+    assert_eq!(traces(&complexity_plot(&[]).to_json()).len(), 5);
+    assert!(traces(&species_plot(&[]).to_json()).is_empty());
+}
+
+#[test]
+fn writes_one_page_per_plot() {
+    let directory = std::env::temp_dir().join(format!("aster_run_plots_test_{}", std::process::id())).join("nested");
+    write_run_plots(&summaries(), &directory).unwrap();
+    for file in ["fitness.html", "complexity.html", "species.html"] {
+        let page = std::fs::read_to_string(directory.join(file)).unwrap();
+        assert!(page.contains("<html"), "{}", file);
+    }
+    write_run_plots(&[], &directory).unwrap();
+    std::fs::remove_dir_all(directory.parent().unwrap()).unwrap();
+}
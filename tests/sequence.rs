@@ -0,0 +1,43 @@
+mod common;
+
+use aster_ml::{convert_id_to_f32, Gene, Genome, Network, SequenceTask};
+
+const TASKS: [SequenceTask; 4] = [
+    SequenceTask::DelayedRecall { delay: 3 },
+    SequenceTask::Copy { length: 3 },
+    SequenceTask::BitCounting { length: 4 },
+    SequenceTask::TMaze { corridor: 4 },
+];
+
+#[test]
+fn feed_forward_networks_score_at_most_half() {
+    for task in TASKS {
+        let trials = task.trials(16, 7);
+        for seed in 0..12 {
+            let genome = common::random_genome(seed, task.input_count(), task.output_count(), 30);
+            let mut network = Network::from_genome(genome);
+            assert!(!network.is_recurrent());
+            let score = task.evaluate(&mut network, &trials);
+            assert!(score.accuracy <= 0.5, "{:?} seed {} scored {}", task, seed, score.accuracy);
+        }
+    }
+}
+
+#[test]
+fn recurrent_synapses_make_a_network_recurrent() {
+    let recurrent = (0..20)
+        .map(|seed| Network::from_genome(common::random_recurrent_genome(seed, 2, 1, 30)))
+        .filter(Network::is_recurrent)
+        .count();
+    assert!(recurrent > 0);
+}
+
+#[test]
+fn synapse_to_a_missing_neuron_is_not_recurrent() {
+    let mut genome = Genome::new(1, 1);
+    genome.genes.insert(
+        1,
+        Gene { id: [0, 0], flag: [10, 10], local_data: convert_id_to_f32([7, 7]), extern_data: 1.0 },
+    );
+    assert!(!Network::from_genome(genome).is_recurrent());
+}
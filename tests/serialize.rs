@@ -0,0 +1,100 @@
+mod common;
+
+use aster_ml::{convert_id_to_f32, Gene, Genome, GenomeError, GENOME_FORMAT_VERSION};
+
+fn synapse(from: [u8; 2], to: [u8; 2]) -> Gene {
+    Gene { id: from, flag: [10, 10], local_data: convert_id_to_f32(to), extern_data: 0.5 }
+}
+
+/// Two inputs, one output, hidden `[0, 1]` under input 0 and synapses along the tree and to the output
+fn small_genome() -> Genome {
+    let mut genome = Genome::new(2, 1);
+    genome.genes[0].flag[1] = 1;
+    genome.genes.push(Gene { id: [0, 1], flag: [2, 0], local_data: 0.1, extern_data: 0.0 });
+    genome.genes.push(synapse([0, 0], [0, 1]));
+    genome.genes.push(synapse([0, 1], [255, 0]));
+    genome.genes.push(synapse([1, 0], [255, 0]));
+    genome
+}
+
+fn invalid(edit: impl FnOnce(&mut Genome)) -> GenomeError {
+    let mut genome = small_genome();
+    edit(&mut genome);
+    genome.validate().unwrap_err()
+}
+
+#[test]
+fn mutated_genomes_validate_and_round_trip() {
+    small_genome().validate().unwrap();
+    for seed in 0..40 {
+        let genome = common::churned_genome(seed, 3, 2, 60);
+        genome.validate().unwrap_or_else(|err| panic!("seed {}: {}", seed, err));
+        let decoded = Genome::from_bytes(&genome.to_bytes()).unwrap();
+        common::assert_same_genes(&genome, &decoded);
+        assert_eq!(decoded.config.connection_bias, genome.config.connection_bias);
+        assert_eq!(decoded.config.inclusion_probability, genome.config.inclusion_probability);
+    }
+}
+
+#[test]
+fn rejects_bad_headers() {
+    let bytes = small_genome().to_bytes();
+    assert!(matches!(Genome::from_bytes(b"NOPE and some more bytes to fill a header"), Err(GenomeError::BadMagic(found)) if &found == b"NOPE"));
+
+    let mut version = bytes.clone();
+    version[4..6].copy_from_slice(&(GENOME_FORMAT_VERSION + 1).to_le_bytes());
+    assert!(matches!(Genome::from_bytes(&version), Err(GenomeError::UnsupportedVersion(v)) if v == GENOME_FORMAT_VERSION + 1));
+
+    let mut counts = bytes.clone();
+    counts[6..8].copy_from_slice(&3u16.to_le_bytes());
+    assert!(matches!(Genome::from_bytes(&counts), Err(GenomeError::CountMismatch { inputs: 2, expected_inputs: 3, .. })));
+}
+
+#[test]
+fn rejects_truncated_and_trailing_bytes() {
+    let bytes = small_genome().to_bytes();
+    for len in [0, 3, 10, bytes.len() - 1] {
+        match Genome::from_bytes(&bytes[..len]) {
+            Err(GenomeError::Truncated { found, .. }) => assert_eq!(found, len),
+            other => panic!("{} bytes: expected truncation, got {:?}", len, other),
+        }
+    }
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(matches!(Genome::from_bytes(&trailing), Err(GenomeError::TrailingBytes(1))));
+}
+
+#[test]
+fn reports_each_validation_error() {
+    assert!(matches!(invalid(|g| g.genes[3].flag[0] = 7), GenomeError::UnknownGeneType { gene: 3, flag: 7 }));
+    assert!(matches!(invalid(|g| g.genes[4].flag[1] = 12), GenomeError::UnknownSynapseStatus { gene: 4, flag: 12 }));
+    assert!(matches!(invalid(|g| g.genes[3].local_data = f32::NAN), GenomeError::NotFinite { gene: 3 }));
+    assert!(matches!(invalid(|g| g.genes[2].id = [255, 1]), GenomeError::InvalidNeuronId { gene: 2, id: [255, 1] }));
+    assert!(matches!(invalid(|g| g.genes[3].id = [0, 0]), GenomeError::InvalidNeuronId { gene: 3, id: [0, 0] }));
+    assert!(matches!(invalid(|g| g.genes[1].id = [5, 0]), GenomeError::InvalidNeuronId { gene: 1, id: [5, 0] }));
+    assert!(matches!(invalid(|g| g.genes.push(g.genes[2].clone())), GenomeError::DuplicateNeuron([255, 0])));
+    assert!(matches!(
+        invalid(|g| g.genes.push(synapse([1, 0], [255, 0]))),
+        GenomeError::DuplicateSynapse { from: [1, 0], to: [255, 0] }
+    ));
+    assert!(matches!(
+        invalid(|g| g.genes.push(synapse([1, 0], [200, 0]))),
+        GenomeError::DanglingSynapse { from: [1, 0], to: [200, 0] }
+    ));
+    assert!(matches!(
+        invalid(|g| g.genes.push(synapse([1, 0], [0, 0]))),
+        GenomeError::DanglingSynapse { from: [1, 0], to: [0, 0] }
+    ));
+    assert!(matches!(
+        invalid(|g| g.genes.push(Gene { id: [1, 4], flag: [2, 0], local_data: 0.0, extern_data: 0.0 })),
+        GenomeError::MissingTreeParent { id: [1, 4], parent: [1, 1] }
+    ));
+    assert!(matches!(
+        invalid(|g| g.genes[0].flag[1] = 2),
+        GenomeError::ChildCountMismatch { id: [0, 0], count: 2, found: 1 }
+    ));
+    assert!(matches!(
+        invalid(|g| g.genes[1].flag[1] = 1),
+        GenomeError::ChildCountMismatch { id: [1, 0], count: 1, found: 0 }
+    ));
+}
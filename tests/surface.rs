@@ -0,0 +1,77 @@
+mod common;
+
+use aster_ml::{Dataset, JsonValue, Network};
+
+fn regression(targets: &[f32]) -> Dataset {
+    Dataset {
+        feature_names: vec!["x".to_string(), "y".to_string()],
+        features: (0..targets.len()).map(|row| vec![row as f32, -(row as f32)]).collect(),
+        targets: targets.iter().map(|&target| vec![target]).collect(),
+        class_names: Vec::new(),
+    }
+}
+
+fn trace(plot: &plotly::Plot, index: usize) -> JsonValue {
+    let figure: JsonValue = plot.to_json().parse().unwrap();
+    match figure.get("data") {
+        Some(JsonValue::Array(traces)) => traces[index].clone(),
+        other => panic!("expected a data array, got {:?}", other),
+    }
+}
+
+fn number(value: &JsonValue) -> f64 {
+    match value {
+        JsonValue::Number(number) => number.parse().unwrap(),
+        other => panic!("expected a number, got {}", other),
+    }
+}
+
+fn numbers(value: &JsonValue) -> Vec<f64> {
+    match value {
+        JsonValue::Array(values) => values.iter().map(number).collect(),
+        other => panic!("expected an array, got {}", other),
+    }
+}
+
+#[test]
+fn surface_values_match_batch_activation() {
+    let network = Network::from_genome(common::random_genome(4, 2, 2, 20));
+    let plot = network.plot_response_surface(1, (-1.0, 1.0), (0.0, 2.0), 2);
+    let heatmap = trace(&plot, 0);
+    assert_eq!(numbers(heatmap.get("x").unwrap()), [-1.0, 1.0]);
+    assert_eq!(numbers(heatmap.get("y").unwrap()), [0.0, 2.0]);
+
+    // Rows of z run along x, one row per y
+    let expected = network.activate_batch(&[[-1.0, 0.0], [1.0, 0.0], [-1.0, 2.0], [1.0, 2.0]]);
+    let z: Vec<Vec<f64>> = match heatmap.get("z") {
+        Some(JsonValue::Array(rows)) => rows.iter().map(numbers).collect(),
+        other => panic!("expected z rows, got {:?}", other),
+    };
+    assert_eq!(z.len(), 2);
+    for (row, values) in z.iter().enumerate() {
+        for (column, &value) in values.iter().enumerate() {
+            assert_eq!(value as f32, expected[row * 2 + column][1], "row {} column {}", row, column);
+        }
+    }
+}
+
+#[test]
+fn regression_points_use_the_target_range() {
+    let network = Network::from_genome(common::random_genome(4, 2, 1, 10));
+    let plot = network.plot_response_surface_with_points(0, (-1.0, 1.0), (-1.0, 1.0), 3, &regression(&[2.0, 5.5, 3.0]));
+    let marker = trace(&plot, 2).get("marker").unwrap().clone();
+    assert_eq!(numbers(marker.get("color").unwrap()), [2.0, 5.5, 3.0]);
+    assert_eq!(number(marker.get("cmin").unwrap()), 2.0);
+    assert_eq!(number(marker.get("cmax").unwrap()), 5.5);
+
+    let plot = network.plot_response_surface_with_points(0, (-1.0, 1.0), (-1.0, 1.0), 3, &regression(&[0.5, 0.5]));
+    let marker = trace(&plot, 2).get("marker").unwrap().clone();
+    assert_eq!((number(marker.get("cmin").unwrap()), number(marker.get("cmax").unwrap())), (-0.5, 1.5));
+}
+
+#[test]
+#[should_panic(expected = "Cannot color points by target column 1")]
+fn regression_points_need_the_target_column() {
+    let network = Network::from_genome(common::random_genome(4, 2, 2, 10));
+    network.plot_response_surface_with_points(1, (-1.0, 1.0), (-1.0, 1.0), 3, &regression(&[1.0, 2.0]));
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use aster_ml::{Genome, TreeCharset};
+use common::{hidden, synapse};
+
+#[test]
+fn renders_mutated_genomes() {
+    for seed in 0..10 {
+        let genome = common::churned_genome(seed, 3, 2, 40);
+        let text = genome.render_tree(TreeCharset::Ascii);
+        for input in 0..3 {
+            assert!(text.contains(&format!("Tree {}\n", input)), "{}", text);
+        }
+    }
+}
+
+#[test]
+fn renders_hidden_neurons_outside_the_tree() {
+    // An orphan whose parent [1, 1] is missing, a neuron without a tree position, and a subtree
+    // in tree 3, which has no input
+    let mut genome = Genome::new(2, 1);
+    genome.genes.extend([
+        hidden([1, 3], 0, 0.0),
+        hidden([7, 0], 0, 0.0),
+        hidden([3, 1], 1, 0.0),
+        hidden([3, 3], 0, 0.0),
+        synapse([7, 0], [255, 0], 1.0, true),
+        synapse([3, 1], [3, 3], 0.5, true),
+    ]);
+    let text = genome.render_tree(TreeCharset::Ascii);
+    assert!(text.contains("`-? [1:3] hidden bias 0  detached, parent removed\n"), "{}", text);
+    let unplaced = "\
+Unplaced
+[3:1] hidden bias 0  no input for its tree
+`-L [3:3] hidden bias 0  w=+0.500
+[7:0] hidden bias 0  no tree position
+     -> [255:0] w=+1.000
+Outputs
+";
+    assert!(text.contains(unplaced), "{}", text);
+}
+
+/// One input with a two-level tree: cross-links, a disabled tree edge, a missing tree synapse and a recurrent link
+#[test]
+fn renders_a_known_tree_exactly() {
+    let mut genome = Genome::new(1, 1);
+    genome.genes[0].flag[1] = 2;
+    genome.genes.extend([
+        hidden([0, 1], 2, 0.5),
+        hidden([0, 2], 0, -1.0),
+        hidden([0, 3], 0, 0.0),
+        hidden([0, 4], 0, 0.0),
+        synapse([0, 0], [0, 1], 1.0, true),
+        synapse([0, 0], [0, 2], -0.5, false),
+        synapse([0, 1], [0, 3], 0.25, true),
+        synapse([0, 2], [0, 1], 1.5, true),
+        synapse([0, 3], [255, 0], 2.0, true),
+        synapse([0, 4], [255, 0], -2.0, false),
+    ]);
+    let expected = "\
+Tree 0
+[0:0] input bias 0
+|-L [0:1] hidden bias 0.5  w=+1.000
+|  |-L [0:3] hidden bias 0  w=+0.250
+|  |       -> [255:0] w=+2.000
+|  `-R [0:4] hidden bias 0  no synapse from parent
+|          -> [255:0] w=-2.000 disabled
+`-R [0:2] hidden bias -1  w=-0.500 disabled
+        -> [0:1] w=+1.500 recurrent
+Outputs
+[255:0] output bias 0  <- 2 incoming
+";
+    assert_eq!(genome.render_tree(TreeCharset::Ascii), expected);
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use aster_ml::{Genome, GenomeError};
+
+fn syntax_error(text: &str) -> (usize, usize, String) {
+    match text.parse::<Genome>() {
+        Err(GenomeError::Syntax { line, column, message }) => (line, column, message),
+        other => panic!("expected a syntax error for {:?}, got {:?}", text, other),
+    }
+}
+
+fn position(text: &str) -> (usize, usize) {
+    let (line, column, _) = syntax_error(text);
+    (line, column)
+}
+
+#[test]
+fn text_round_trips() {
+    for seed in 0..20 {
+        let genome = common::churned_genome(seed, 3, 2, 50);
+        let text = genome.to_text().unwrap();
+        let parsed: Genome = text.parse().unwrap();
+        common::assert_same_genes(&genome, &parsed);
+        assert_eq!(parsed.config.connection_bias, genome.config.connection_bias);
+        assert_eq!(parsed.config.inclusion_probability.to_bits(), genome.config.inclusion_probability.to_bits());
+        assert_eq!(parsed.to_text().unwrap(), text);
+    }
+}
+
+#[test]
+fn accepts_comments_blank_lines_and_defaults() {
+    let text = "# two inputs\n\ninput 0:0\ninput 1:0   # no children\noutput 255:0 bias=0.5\nsynapse 1:0 -> 255:0 weight=2\n";
+    let genome: Genome = text.parse().unwrap();
+    assert_eq!(genome.input_count(), 2);
+    assert_eq!(genome.genes[2].local_data, 0.5);
+    assert_eq!(genome.genes[3].extern_data, 2.0);
+}
+
+#[test]
+fn errors_point_at_line_and_column() {
+    assert_eq!(position("input 0:0\n  neuron 1:0"), (2, 3));
+    assert_eq!(position("input 0:0\noutput 255:0 bias=x"), (2, 19));
+    let (line, column, message) = syntax_error("input 0:0\noutput 255:0\n\nsynapse 0:0 => 255:0");
+    assert_eq!((line, column), (4, 13));
+    assert!(message.contains("->"), "{}", message);
+    assert_eq!(position("input 0:300"), (1, 7));
+    assert_eq!(position("input 0:0 depth=2"), (1, 11));
+    assert_eq!(position("config connection_bias=1\nconfig connection_bias=2"), (2, 1));
+}
+
+#[test]
+fn invalid_genes_have_no_text_form() {
+    let mut genome = Genome::new(2, 1);
+    genome.genes[1].flag[0] = 7;
+    assert!(matches!(genome.to_text(), Err(GenomeError::UnknownGeneType { gene: 1, flag: 7 })));
+
+    let mut genome = common::random_genome(2, 2, 1, 10);
+    let synapse = genome.genes.iter().position(|gene| gene.flag[0] == 10).unwrap();
+    genome.genes[synapse].flag[1] = 3;
+    assert!(matches!(genome.to_text(), Err(GenomeError::UnknownSynapseStatus { flag: 3, .. })));
+}
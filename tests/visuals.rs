@@ -0,0 +1,173 @@
+mod common;
+
+use aster_ml::{Genome, JsonValue, Network, NodeColor, PlotFormat, VisualizeOptions};
+use common::{hidden, synapse};
+
+/// Two inputs and one output: [0, 0] -> [0, 1] (weight 2) -> [255, 0] (weight -1),
+/// and a disabled [1, 0] -> [255, 0] (weight 0.5). The hidden neuron has bias -0.5.
+fn sample_genome() -> Genome {
+    let mut genome = Genome::new(2, 1);
+    genome.genes[0].flag[1] = 1;
+    genome.genes.extend([
+        hidden([0, 1], 0, -0.5),
+        synapse([0, 0], [0, 1], 2.0, true),
+        synapse([0, 1], [255, 0], -1.0, true),
+        synapse([1, 0], [255, 0], 0.5, false),
+    ]);
+    genome
+}
+
+fn parse_figure(json: &str) -> JsonValue {
+    json.parse().unwrap_or_else(|err| panic!("plot JSON does not parse: {}\n{}", err, json))
+}
+
+fn at<'a>(value: &'a JsonValue, path: &[&str]) -> &'a JsonValue {
+    path.iter().fold(value, |value, key| value.get(key).unwrap_or_else(|| panic!("missing {:?}", path)))
+}
+
+fn text(value: &JsonValue) -> &str {
+    match value {
+        JsonValue::String(text) => text,
+        other => panic!("expected a string, got {}", other),
+    }
+}
+
+fn number(value: &JsonValue) -> f64 {
+    match value {
+        JsonValue::Number(number) => number.parse().unwrap(),
+        other => panic!("expected a number, got {}", other),
+    }
+}
+
+fn items(value: &JsonValue) -> &[JsonValue] {
+    match value {
+        JsonValue::Array(items) => items,
+        other => panic!("expected an array, got {}", other),
+    }
+}
+
+/// Traces of the figure by name, in plot order
+fn traces<'a>(figure: &'a JsonValue, name: &str) -> Vec<&'a JsonValue> {
+    items(at(figure, &["data"])).iter().filter(|trace| text(at(trace, &["name"])) == name).collect()
+}
+
+fn neuron_trace(figure: &JsonValue) -> &JsonValue {
+    let neurons = traces(figure, "Neurons");
+    assert_eq!(neurons.len(), 1);
+    neurons[0]
+}
+
+#[test]
+fn json_plot_carries_the_layout_options() {
+    let options = VisualizeOptions {
+        title: Some("Champion".to_string()),
+        width: Some(640),
+        height: Some(480),
+        hide_axes: true,
+        ..VisualizeOptions::default()
+    };
+    let figure = parse_figure(&sample_genome().visualize_to_string(&options, PlotFormat::Json));
+    assert_eq!(text(at(&figure, &["layout", "title", "text"])), "Champion");
+    assert_eq!(number(at(&figure, &["layout", "width"])), 640.0);
+    assert_eq!(number(at(&figure, &["layout", "height"])), 480.0);
+    for axis in ["xaxis", "yaxis"] {
+        assert_eq!(at(&figure, &["layout", axis, "visible"]), &JsonValue::Bool(false));
+        assert_eq!(at(&figure, &["layout", axis, "showgrid"]), &JsonValue::Bool(false));
+    }
+
+    let plain = parse_figure(&sample_genome().visualize_to_string(&VisualizeOptions::default(), PlotFormat::Json));
+    let layout = at(&plain, &["layout"]);
+    assert!(layout.get("title").is_none() && layout.get("width").is_none() && layout.get("xaxis").is_none());
+}
+
+#[test]
+fn node_labels_switch_on_text_mode() {
+    let options = VisualizeOptions { node_labels: true, ..VisualizeOptions::default() };
+    let figure = parse_figure(&sample_genome().visualize_to_string(&options, PlotFormat::Json));
+    let neurons = neuron_trace(&figure);
+    assert_eq!(text(at(neurons, &["mode"])), "markers+text");
+    let labels: Vec<&str> = items(at(neurons, &["text"])).iter().map(text).collect();
+    assert_eq!(labels, ["0:0", "1:0", "255:0", "0:1"]);
+
+    let figure = parse_figure(&sample_genome().visualize_to_string(&VisualizeOptions::default(), PlotFormat::Json));
+    assert_eq!(text(at(neuron_trace(&figure), &["mode"])), "markers");
+    assert!(neuron_trace(&figure).get("text").is_none());
+}
+
+#[test]
+fn weighted_edges_are_colored_by_sign() {
+    let options = VisualizeOptions { weighted_edges: true, ..VisualizeOptions::default() };
+    let figure = parse_figure(&sample_genome().visualize_to_string(&options, PlotFormat::Json));
+    let color = |trace: &JsonValue| text(at(trace, &["line", "color"])).to_string();
+    let width = |trace: &JsonValue| number(at(trace, &["line", "width"]));
+
+    let positive = traces(&figure, "Positive weight");
+    assert_eq!(positive.len(), 1);
+    assert_eq!(color(positive[0]), "#2b6cb0");
+    assert_eq!(width(positive[0]), 5.0);
+
+    let negative = traces(&figure, "Negative weight");
+    assert_eq!(negative.len(), 1);
+    assert_eq!(color(negative[0]), "#c53030");
+    assert_eq!(width(negative[0]), 2.75);
+
+    let disabled = traces(&figure, "Disabled");
+    assert_eq!(disabled.len(), 1);
+    assert_eq!(text(at(disabled[0], &["line", "dash"])), "dash");
+    assert_eq!(number(at(disabled[0], &["opacity"])), 0.5);
+}
+
+#[test]
+fn bias_colors_use_a_symmetric_scale() {
+    let options = VisualizeOptions { node_color: NodeColor::Bias, ..VisualizeOptions::default() };
+    let figure = parse_figure(&sample_genome().visualize_to_string(&options, PlotFormat::Json));
+    let marker = at(neuron_trace(&figure), &["marker"]);
+    let colors: Vec<f64> = items(at(marker, &["color"])).iter().map(number).collect();
+    assert_eq!(colors, [0.0, 0.0, 0.0, -0.5]);
+    assert_eq!(number(at(marker, &["cmin"])), -0.5);
+    assert_eq!(number(at(marker, &["cmax"])), 0.5);
+    assert_eq!(text(at(marker, &["colorbar", "title", "text"])), "Bias");
+
+    // Type colors are names, one per neuron
+    let figure = parse_figure(&sample_genome().visualize_to_string(&VisualizeOptions::default(), PlotFormat::Json));
+    let colors: Vec<&str> = items(at(neuron_trace(&figure), &["marker", "color"])).iter().map(text).collect();
+    assert_eq!(colors, ["blue", "blue", "red", "green"]);
+}
+
+#[test]
+fn activation_colors_need_a_network() {
+    let options = VisualizeOptions { node_color: NodeColor::Activation, ..VisualizeOptions::default() };
+    let genome = parse_figure(&sample_genome().visualize_to_string(&options, PlotFormat::Json));
+    assert!(at(neuron_trace(&genome), &["marker"]).get("colorbar").is_none());
+
+    let mut network = Network::from_genome(sample_genome());
+    network.activate(&[1.0, 1.0]);
+    let figure = parse_figure(&network.visualize_to_string(&options, PlotFormat::Json));
+    let marker = at(neuron_trace(&figure), &["marker"]);
+    assert_eq!(text(at(marker, &["colorbar", "title", "text"])), "Activation");
+    let hidden = number(&items(at(marker, &["color"]))[3]);
+    assert_eq!(hidden as f32, network.neurons[&[0, 1]].activation);
+}
+
+#[test]
+fn file_format_follows_the_extension() {
+    let directory = std::env::temp_dir().join(format!("aster_visuals_test_{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let options = VisualizeOptions { title: Some("File".to_string()), ..VisualizeOptions::default() };
+    let genome = sample_genome();
+
+    for name in ["plot.json", "plot.JSON"] {
+        let path = directory.join(name);
+        genome.visualize_to_file(&path, &options).unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(written, genome.visualize_to_string(&options, PlotFormat::Json));
+        assert_eq!(text(at(&parse_figure(&written), &["layout", "title", "text"])), "File");
+    }
+    let path = directory.join("plot.html");
+    genome.visualize_to_file(&path, &options).unwrap();
+    let written = std::fs::read_to_string(&path).unwrap();
+    assert!(written.contains("<html"), "{}", &written[..written.len().min(200)]);
+    assert!(written.parse::<JsonValue>().is_err());
+
+    std::fs::remove_dir_all(directory).unwrap();
+}